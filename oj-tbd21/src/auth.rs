@@ -0,0 +1,207 @@
+use super::*;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rand::{rngs::OsRng, RngCore};
+
+///Body of password request
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct PasswordRequest {
+    password: String,
+}
+
+///Body of login request
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct LoginRequest {
+    user_id: usize,
+    password: String,
+}
+
+///Body of login response
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct LoginResponse {
+    token: String,
+    expires_in: u64,
+}
+
+///Claims carried by a login token
+#[derive(Deserialize, Serialize, Clone, Debug)]
+struct Claims {
+    sub: usize,
+    exp: u64,
+}
+
+///Generates a random secret for signing tokens from 32 bytes of the OS random source, used if none is configured
+///Tokens signed with it become invalid once the server restarts
+pub fn default_jwt_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn default_token_lifetime() -> u64 {
+    3600
+}
+
+///Hashes a password with bcrypt
+///Hashing is slow on purpose, so it runs on a blocking thread instead of a worker
+async fn hash_password(password: String) -> Result<String, Box<dyn Error>> {
+    Ok(web::block(move || bcrypt::hash(password, bcrypt::DEFAULT_COST)).await??)
+}
+
+///Verifies a password against its bcrypt hash on a blocking thread, see hash_password
+async fn verify_password(password: String, hash: String) -> Result<bool, Box<dyn Error>> {
+    Ok(web::block(move || bcrypt::verify(password, &hash)).await??)
+}
+
+///Issues a token for the user which expires after the configured lifetime
+fn issue_token(user_id: usize, server: &Server) -> Result<String, Box<dyn Error>> {
+    let claims = Claims {
+        sub: user_id,
+        exp: Utc::now().timestamp() as u64 + server.token_lifetime,
+    };
+    Ok(encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(server.jwt_secret.as_bytes()),
+    )?)
+}
+
+///Gets the bearer token of the request, if any
+fn bearer_token(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+///Gets the user a token was issued to, if the token is valid and unexpired
+fn token_subject(token: &str, config: &Config) -> Result<usize, ApiError> {
+    //No leeway so that tokens are rejected as soon as they expire
+    let mut validation = Validation::default();
+    validation.leeway = 0;
+    match decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(config.server.jwt_secret.as_bytes()),
+        &validation,
+    ) {
+        Ok(data) => Ok(data.claims.sub),
+        Err(e) => Err(unauthorized(Message::InvalidToken(e.to_string()))),
+    }
+}
+
+///Checks whether the request may act on behalf of the user
+///Users without a password are open to everyone, otherwise a valid token of the user is required
+///Returns: Result<(), ApiError> -- the error if the request is not authorized
+pub fn authorize(req: &HttpRequest, user: &User, config: &Config) -> Result<(), ApiError> {
+    if user.password_hash.is_none() {
+        return Ok(());
+    }
+
+    let token = match bearer_token(req) {
+        Some(token) => token,
+        None => return Err(unauthorized(Message::TokenRequired(user.id.unwrap()))),
+    };
+    match token_subject(token, config)? {
+        subject if subject == user.id.unwrap() => Ok(()),
+        _ => Err(unauthorized(Message::TokenOfOtherUser(user.id.unwrap()))),
+    }
+}
+
+///Checks whether the request may set the password of the user
+///A valid token of the user or of the administrator (root) is required, except that in open mode
+///a user without a password may set the first one without a token, as anyone can act on behalf of them
+///Returns: Result<(), ApiError> -- the error if the request is not authorized
+fn authorize_password(req: &HttpRequest, user: &User, config: &Config) -> Result<(), ApiError> {
+    let id = user.id.unwrap();
+    match bearer_token(req) {
+        Some(token) => match token_subject(token, config)? {
+            subject if subject == id || subject == 0 => Ok(()),
+            _ => Err(unauthorized(Message::TokenOfOtherUser(id))),
+        },
+        None if config.server.open_mode && user.password_hash.is_none() => Ok(()),
+        None => Err(unauthorized(Message::TokenRequired(id))),
+    }
+}
+
+///Shortcut of authorize for handlers
+///Returns: Option<HttpResponse> -- the error response if the request is not authorized
+pub fn check_auth(req: &HttpRequest, user: &User, config: &Config) -> Option<HttpResponse> {
+    authorize(req, user, config).err().map(|e| e.response(req))
+}
+
+///POST requests for "/users/{userId}/password" handler
+#[post("/users/{userId}/password")]
+async fn post_users_password(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    request: web::Json<PasswordRequest>,
+    config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+    let request = request.into_inner();
+    let pool = pool.into_inner();
+
+    log::info!(target: "post_users_password_handler", "Handling POST for user {}", id);
+
+    let user = match oj_try!(User::select_by_id(id, &pool)) {
+        Some(user) => user,
+        None => {
+            return not_found(Resource::User, id).response(&req);
+        }
+    };
+
+    if let Err(e) = authorize_password(&req, &user, &config) {
+        return e.response(&req);
+    }
+
+    if request.password.is_empty() {
+        return invalid_argument(Message::EmptyPassword).response(&req);
+    }
+
+    let hash = oj_try!(hash_password(request.password).await);
+    oj_try!(User::update_password_hash(id, &hash, &pool));
+
+    HttpResponse::Ok().body(serde_json::to_string(&user).unwrap())
+}
+
+///POST requests for "/auth/login" handler
+#[post("/auth/login")]
+async fn post_auth_login(
+    req: HttpRequest,
+    request: web::Json<LoginRequest>,
+    config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let request = request.into_inner();
+    let pool = pool.into_inner();
+
+    log::info!(target: "post_auth_login_handler", "Handling POST for user {}", request.user_id);
+
+    let user = match oj_try!(User::select_by_id(request.user_id, &pool)) {
+        Some(user) => user,
+        None => {
+            return not_found(Resource::User, request.user_id).response(&req);
+        }
+    };
+
+    let hash = match user.password_hash {
+        Some(hash) => hash,
+        None => {
+            return invalid_state(Message::NoPassword(request.user_id)).response(&req);
+        }
+    };
+
+    if !oj_try!(verify_password(request.password, hash).await) {
+        return unauthorized(Message::WrongPassword(request.user_id)).response(&req);
+    }
+
+    HttpResponse::Ok().body(
+        serde_json::to_string(&LoginResponse {
+            token: oj_try!(issue_token(request.user_id, &config.server)),
+            expires_in: config.server.token_lifetime,
+        })
+        .unwrap(),
+    )
+}
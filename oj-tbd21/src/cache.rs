@@ -0,0 +1,72 @@
+use super::*;
+use std::{collections::BTreeMap, sync::Mutex};
+
+///Ranklists computed so far, shared by all workers
+///Jobs, users and contests invalidate the entries depending on them when written to the database
+pub static RANKLIST_CACHE: RanklistCache = RanklistCache::new();
+
+///Key of a cached ranklist, the contest id, the ranking rule and the format
+pub type RanklistKey = (usize, RankingRule, RanklistFormat);
+
+///Cache of rendered ranklists
+pub struct RanklistCache {
+    inner: Mutex<RanklistCacheInner>,
+}
+
+struct RanklistCacheInner {
+    ///Bumped on every invalidation, so that a ranklist computed from outdated data is not stored
+    generation: usize,
+    entries: BTreeMap<RanklistKey, String>,
+}
+
+impl RanklistCache {
+    ///Makes an empty RanklistCache
+    const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RanklistCacheInner {
+                generation: 0,
+                entries: BTreeMap::new(),
+            }),
+        }
+    }
+
+    ///Gets the current generation, to be passed to put once the ranklist is computed
+    pub fn generation(&self) -> usize {
+        self.inner.lock().unwrap().generation
+    }
+
+    ///Gets a cached ranklist
+    pub fn get(&self, key: &RanklistKey) -> Option<String> {
+        self.inner.lock().unwrap().entries.get(key).cloned()
+    }
+
+    ///Stores a ranklist computed since the given generation
+    ///It is dropped if anything has been invalidated in the meantime
+    pub fn put(&self, key: RanklistKey, generation: usize, ranklist: String) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.generation == generation {
+            inner.entries.insert(key, ranklist);
+        }
+    }
+
+    ///Drops the ranklists a job of the given contest appears in
+    ///The global ranklist (contest 0) is always dropped, and a job of contest 0 appears in every ranklist
+    pub fn invalidate_contest(&self, contest_id: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.generation += 1;
+        if contest_id == 0 {
+            inner.entries.clear();
+        } else {
+            inner
+                .entries
+                .retain(|(id, _, _), _| *id != contest_id && *id != 0);
+        }
+    }
+
+    ///Drops all the ranklists, e.g. when users or contests change
+    pub fn invalidate_all(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.generation += 1;
+        inner.entries.clear();
+    }
+}
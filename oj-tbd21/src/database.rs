@@ -5,6 +5,56 @@ use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::params;
 use Error;
 
+///Timeout in milliseconds for a connection to wait for a locked database
+const BUSY_TIMEOUT: u32 = 5000;
+
+///SQLite connection initialization
+///WAL mode lets the readers go on while a job is being written, the busy timeout makes
+///concurrent writers wait for the lock instead of failing with "database is locked"
+pub fn connection_init(conn: &mut rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(&format!(
+        "PRAGMA journal_mode = WAL;
+        PRAGMA busy_timeout = {};",
+        BUSY_TIMEOUT
+    ))
+}
+
+///Columns added to the tables after their first version, with the values the existing rows get
+///A NOT NULL column needs a default to be added, which is what the API shows for missing data
+const ADDED_COLUMNS: [(&str, &str, &str); 5] = [
+    ("jobs", "language_command", "TEXT NOT NULL DEFAULT 'null'"),
+    ("jobs", "config_version", "TEXT NOT NULL DEFAULT ''"),
+    ("users", "password_hash", "TEXT"),
+    ("contests", "limit_overrides", "TEXT NOT NULL DEFAULT '[]'"),
+    (
+        "contests",
+        "per_problem_submission_limit",
+        "TEXT NOT NULL DEFAULT 'null'",
+    ),
+];
+
+///Adds the columns missing from the tables of a database made by an earlier version
+///CREATE TABLE IF NOT EXISTS leaves an existing table as it is, so the columns are checked one by one
+fn migrate_columns(pool: &Pool<SqliteConnectionManager>) -> Result<(), Box<dyn Error>> {
+    let conn = pool.get()?;
+    for (table, column, definition) in ADDED_COLUMNS {
+        let exists = conn
+            .prepare(&format!("PRAGMA table_info({})", table))?
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?
+            .iter()
+            .any(|name| name == column);
+        if !exists {
+            log::info!(target: "database_init", "Adding column {} to table {}", column, table);
+            conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
 ///SQLite database initialization
 pub fn database_init(pool: &Pool<SqliteConnectionManager>) -> Result<(), Box<dyn Error>> {
     pool.get()?.execute(
@@ -20,14 +70,17 @@ pub fn database_init(pool: &Pool<SqliteConnectionManager>) -> Result<(), Box<dyn
             state               TEXT NOT NULL,
             result              TEXT NOT NULL,
             score               INTEGER,
-            cases               TEXT NOT NULL
+            cases               TEXT NOT NULL,
+            language_command    TEXT NOT NULL,
+            config_version      TEXT NOT NULL
         )",
         [],
     )?;
     pool.get()?.execute(
         "CREATE TABLE IF NOT EXISTS users (
             id                  INTEGER PRIMARY KEY,
-            name                TEXT NOT NULL
+            name                TEXT NOT NULL,
+            password_hash       TEXT
         )",
         [],
     )?;
@@ -39,26 +92,144 @@ pub fn database_init(pool: &Pool<SqliteConnectionManager>) -> Result<(), Box<dyn
             to_time                 TEXT NOT NULL,
             problem_ids             TEXT NOT NULL,
             user_ids                TEXT NOT NULL,
-            submission_limit        INTEGER
+            submission_limit        INTEGER,
+            limit_overrides         TEXT NOT NULL,
+            per_problem_submission_limit TEXT NOT NULL
         )",
         [],
     )?;
+    pool.get()?.execute(
+        "CREATE TABLE IF NOT EXISTS announcements (
+            id                  INTEGER PRIMARY KEY,
+            contest_id          INTEGER NOT NULL,
+            content             TEXT NOT NULL,
+            created_time        TEXT NOT NULL,
+            author_id           INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    migrate_columns(pool)?;
     match User::select_by_name("root", pool)? {
         Some(_) => {}
-        None => User {
-            id: Some(0),
-            name: "root".to_string(),
+        None => {
+            User {
+                id: Some(0),
+                name: "root".to_string(),
+                password_hash: None,
+            }
+            .insert(pool)?;
         }
-        .insert(pool)?,
     };
 
     Ok(())
 }
 
+///User in a backup, keeping the password hash which is never shown by the API
+#[derive(Deserialize, Serialize)]
+struct BackupUser {
+    #[serde(flatten)]
+    user: User,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password_hash: Option<String>,
+}
+
+///All the data in the SQLite database, used by `--dump` and `--restore`
+#[derive(Deserialize, Serialize)]
+struct Backup {
+    jobs: Vec<Job>,
+    users: Vec<BackupUser>,
+    contests: Vec<Contest>,
+
+    ///Missing from the backups written before announcements were dumped
+    #[serde(default)]
+    announcements: Vec<Announcement>,
+}
+
+///Writes all the jobs, users, contests and announcements to a JSON file
+pub fn dump_database(
+    filename: &str,
+    pool: &Pool<SqliteConnectionManager>,
+) -> Result<(), Box<dyn Error>> {
+    let backup = Backup {
+        jobs: Job::select_all(pool)?,
+        users: User::select_all(pool)?
+            .into_iter()
+            .map(|user| BackupUser {
+                password_hash: user.password_hash.clone(),
+                user,
+            })
+            .collect(),
+        contests: Contest::select_all(pool)?,
+        announcements: Announcement::select_all(pool)?,
+    };
+    std::fs::write(filename, serde_json::to_string_pretty(&backup)?)?;
+    Ok(())
+}
+
+///Loads the jobs, users, contests and announcements of a JSON file written by dump_database, keeping their ids
+///The database must be fresh, with the root user only
+pub fn restore_database(
+    filename: &str,
+    pool: &Pool<SqliteConnectionManager>,
+) -> Result<(), Box<dyn Error>> {
+    if Job::count(pool)? > 0
+        || Contest::count(pool)? > 0
+        || Announcement::count(pool)? > 0
+        || User::count(pool)? > 1
+    {
+        return Err("Database is not empty, use --flush-data to restore into a fresh one".into());
+    }
+
+    let backup: Backup = serde_json::from_str(&std::fs::read_to_string(filename)?)?;
+    for BackupUser {
+        user,
+        password_hash,
+    } in backup.users
+    {
+        //The root user is created on initialization, so it is updated instead
+        let id = match user.id {
+            Some(id) if User::select_by_id(id, pool)?.is_some() => {
+                user.update(pool)?;
+                id
+            }
+            _ => user.insert(pool)?,
+        };
+        if let Some(hash) = password_hash {
+            User::update_password_hash(id, &hash, pool)?;
+        }
+    }
+    for contest in backup.contests {
+        contest.insert(pool)?;
+    }
+    for announcement in backup.announcements {
+        announcement.restore(pool)?;
+    }
+    for job in backup.jobs {
+        job.restore(pool)?;
+    }
+    Ok(())
+}
+
 impl Job {
-    ///Inserts a job into the SQLite database
-    pub fn insert(&self, pool: &Pool<SqliteConnectionManager>) -> Result<(), Box<dyn Error>> {
-        pool.get()?.execute(
+    ///Inserts a job into the SQLite database and returns its id
+    ///The id is assigned within the insertion so that concurrent submissions never share one
+    pub fn insert(&self, pool: &Pool<SqliteConnectionManager>) -> Result<usize, Box<dyn Error>> {
+        self.insert_with_id(None, pool)
+    }
+
+    ///Inserts a job into the SQLite database keeping its own id, used when restoring a backup
+    pub fn restore(&self, pool: &Pool<SqliteConnectionManager>) -> Result<usize, Box<dyn Error>> {
+        self.insert_with_id(Some(self.id), pool)
+    }
+
+    fn insert_with_id(
+        &self,
+        id: Option<usize>,
+        pool: &Pool<SqliteConnectionManager>,
+    ) -> Result<usize, Box<dyn Error>> {
+        let conn = pool.get()?;
+        conn.execute(
             "INSERT INTO jobs (
                 id,
                 created_time,
@@ -71,12 +242,13 @@ impl Job {
                 state,
                 result,
                 score,
-                cases
+                cases,
+                language_command,
+                config_version
             ) VALUES (
-                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12
+                IFNULL(?14, (SELECT IFNULL(MAX(id) + 1, 0) FROM jobs)), ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13
             )",
             params![
-                self.id,
                 self.created_time.format(FORMAT).to_string(),
                 self.updated_time.format(FORMAT).to_string(),
                 self.submission.source_code,
@@ -87,11 +259,15 @@ impl Job {
                 serde_json::to_string(&self.state)?,
                 serde_json::to_string(&self.result)?,
                 self.score,
-                serde_json::to_string(&self.cases)?
+                serde_json::to_string(&self.cases)?,
+                serde_json::to_string(&self.language_command)?,
+                self.config_version,
+                id
             ],
         )?;
+        RANKLIST_CACHE.invalidate_contest(self.submission.contest_id);
 
-        Ok(())
+        Ok(conn.last_insert_rowid() as usize)
     }
 
     ///Selects all the jobs in the SQLite database
@@ -145,6 +321,11 @@ impl Job {
                     Ok(s) => s,
                     Err(_) => return Err(rusqlite::Error::InvalidQuery),
                 },
+                language_command: match serde_json::from_str(&row.get::<_, String>(12)?) {
+                    Ok(s) => s,
+                    Err(_) => return Err(rusqlite::Error::InvalidQuery),
+                },
+                config_version: row.get(13)?,
             })
         })?;
         Ok(iter.collect::<rusqlite::Result<Vec<Self>>>()?)
@@ -179,8 +360,10 @@ impl Job {
             state = ?8,
             result = ?9,
             score = ?10,
-            cases = ?11
-            WHERE id = ?12",
+            cases = ?11,
+            language_command = ?12,
+            config_version = ?13
+            WHERE id = ?14",
             params![
                 self.created_time.format(FORMAT).to_string(),
                 self.updated_time.format(FORMAT).to_string(),
@@ -193,34 +376,48 @@ impl Job {
                 serde_json::to_string(&self.result)?,
                 self.score,
                 serde_json::to_string(&self.cases)?,
+                serde_json::to_string(&self.language_command)?,
+                self.config_version,
                 self.id,
             ],
         )?;
+        RANKLIST_CACHE.invalidate_contest(self.submission.contest_id);
 
         Ok(())
     }
 }
 
 impl User {
-    ///Inserts a user into the SQLite database
-    pub fn insert(&self, pool: &Pool<SqliteConnectionManager>) -> Result<(), Box<dyn Error>> {
-        pool.get()?.execute(
-            "INSERT INTO users (
-            id,
-            name
-        ) VALUES (
-            ?1,
-            ?2
-        )",
-            params![
-                match self.id {
-                    Some(id) => id,
-                    None => Self::count(&pool)?,
-                },
-                self.name
-            ],
-        )?;
-        Ok(())
+    ///Inserts a user into the SQLite database and returns its id
+    ///If the id is not provided, SQLite assigns one on insertion
+    pub fn insert(&self, pool: &Pool<SqliteConnectionManager>) -> Result<usize, Box<dyn Error>> {
+        let conn = pool.get()?;
+        match self.id {
+            Some(id) => {
+                conn.execute(
+                    "INSERT INTO users (
+                    id,
+                    name
+                ) VALUES (
+                    ?1,
+                    ?2
+                )",
+                    params![id, self.name],
+                )?;
+            }
+            None => {
+                conn.execute(
+                    "INSERT INTO users (
+                    name
+                ) VALUES (
+                    ?1
+                )",
+                    params![self.name],
+                )?;
+            }
+        }
+        RANKLIST_CACHE.invalidate_all();
+        Ok(conn.last_insert_rowid() as usize)
     }
 
     ///Selects all the users in the SQLite database
@@ -231,6 +428,42 @@ impl User {
             Ok(Self {
                 id: row.get(0)?,
                 name: row.get(1)?,
+                password_hash: row.get(2)?,
+            })
+        })?;
+        Ok(iter.collect::<rusqlite::Result<Vec<Self>>>()?)
+    }
+
+    ///Selects the users whose names contain the given substring (ASCII case-insensitive), ordered by id
+    ///At most limit users are returned after skipping the first offset ones
+    pub fn select_filtered(
+        name: Option<&str>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+        pool: &Pool<SqliteConnectionManager>,
+    ) -> Result<Vec<Self>, Box<dyn Error>> {
+        //Escapes the wildcards of LIKE so that the name is matched literally
+        let pattern = format!(
+            "%{}%",
+            name.unwrap_or("")
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        let conn = pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM users
+            WHERE name LIKE ?1 ESCAPE '\\'
+            ORDER BY id
+            LIMIT ?2 OFFSET ?3",
+        )?;
+        //A negative limit means no limit in SQLite
+        let limit = limit.map_or(-1, |limit| limit as i64);
+        let iter = stmt.query_map(params![pattern, limit, offset.unwrap_or(0)], |row| {
+            Ok(Self {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                password_hash: row.get(2)?,
             })
         })?;
         Ok(iter.collect::<rusqlite::Result<Vec<Self>>>()?)
@@ -271,33 +504,57 @@ impl User {
         WHERE id = ?2",
             params![self.name, self.id.unwrap()],
         )?;
+        RANKLIST_CACHE.invalidate_all();
+        Ok(())
+    }
+
+    ///Updates the password hash of the specified user
+    pub fn update_password_hash(
+        id: usize,
+        hash: &str,
+        pool: &Pool<SqliteConnectionManager>,
+    ) -> Result<(), Box<dyn Error>> {
+        pool.get()?.execute(
+            "UPDATE users SET
+        password_hash = ?1
+        WHERE id = ?2",
+            params![hash, id],
+        )?;
         Ok(())
     }
 }
 
 impl Contest {
     ///Inserts a contest into the SQLite database
+    ///If the id is not provided, SQLite assigns one on insertion
     pub fn insert(&self, pool: &Pool<SqliteConnectionManager>) -> Result<(), Box<dyn Error>> {
         pool.get()?.execute(
             "INSERT INTO contests (
+                id,
                 name,
                 from_time,
                 to_time,
                 problem_ids,
                 user_ids,
-                submission_limit
+                submission_limit,
+                limit_overrides,
+                per_problem_submission_limit
             ) VALUES (
-                ?1, ?2, ?3, ?4, ?5, ?6
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9
             )",
             params![
+                self.id,
                 self.name,
                 self.from.format(FORMAT).to_string(),
                 self.to.format(FORMAT).to_string(),
                 serde_json::to_string(&self.problem_ids)?,
                 serde_json::to_string(&self.user_ids)?,
-                self.submission_limit
+                self.submission_limit,
+                serde_json::to_string(&self.limit_overrides)?,
+                serde_json::to_string(&self.per_problem_submission_limit)?
             ],
         )?;
+        RANKLIST_CACHE.invalidate_all();
         Ok(())
     }
 
@@ -342,6 +599,15 @@ impl Contest {
                     Err(_) => return Err(rusqlite::Error::InvalidQuery),
                 },
                 submission_limit: row.get(6)?,
+                limit_overrides: match serde_json::from_str(&row.get::<_, String>(7)?) {
+                    Ok(s) => s,
+                    Err(_) => return Err(rusqlite::Error::InvalidQuery),
+                },
+                per_problem_submission_limit: match serde_json::from_str(&row.get::<_, String>(8)?)
+                {
+                    Ok(s) => s,
+                    Err(_) => return Err(rusqlite::Error::InvalidQuery),
+                },
             })
         })?;
         Ok(iter.collect::<rusqlite::Result<Vec<Contest>>>()?)
@@ -363,8 +629,10 @@ impl Contest {
             to_time = ?3,
             problem_ids = ?4,
             user_ids = ?5,
-            submission_limit = ?6
-            WHERE id = ?7",
+            submission_limit = ?6,
+            limit_overrides = ?7,
+            per_problem_submission_limit = ?8
+            WHERE id = ?9",
             params![
                 self.name,
                 self.from.format(FORMAT).to_string(),
@@ -372,9 +640,12 @@ impl Contest {
                 serde_json::to_string(&self.problem_ids)?,
                 serde_json::to_string(&self.user_ids)?,
                 self.submission_limit,
+                serde_json::to_string(&self.limit_overrides)?,
+                serde_json::to_string(&self.per_problem_submission_limit)?,
                 self.id,
             ],
         )?;
+        RANKLIST_CACHE.invalidate_all();
         Ok(())
     }
 
@@ -398,3 +669,92 @@ impl Contest {
             .find(|contest| contest.name == name))
     }
 }
+
+impl Announcement {
+    ///Inserts an announcement into the SQLite database and returns its id
+    pub fn insert(&self, pool: &Pool<SqliteConnectionManager>) -> Result<usize, Box<dyn Error>> {
+        self.insert_with_id(None, pool)
+    }
+
+    ///Inserts an announcement into the SQLite database keeping its own id, used when restoring a backup
+    pub fn restore(&self, pool: &Pool<SqliteConnectionManager>) -> Result<usize, Box<dyn Error>> {
+        self.insert_with_id(Some(self.id), pool)
+    }
+
+    fn insert_with_id(
+        &self,
+        id: Option<usize>,
+        pool: &Pool<SqliteConnectionManager>,
+    ) -> Result<usize, Box<dyn Error>> {
+        let conn = pool.get()?;
+        conn.execute(
+            "INSERT INTO announcements (
+                id,
+                contest_id,
+                content,
+                created_time,
+                author_id
+            ) VALUES (
+                ?5, ?1, ?2, ?3, ?4
+            )",
+            params![
+                self.contest_id,
+                self.content,
+                self.created_time.format(FORMAT).to_string(),
+                self.author_id,
+                id
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as usize)
+    }
+
+    ///Selects all the announcements in the SQLite database
+    pub fn select_all(pool: &Pool<SqliteConnectionManager>) -> Result<Vec<Self>, Box<dyn Error>> {
+        let conn = pool.get()?;
+        let mut stmt = conn.prepare("SELECT * from announcements")?;
+        let iter = stmt.query_map(params![], Self::from_row)?;
+        Ok(iter.collect::<rusqlite::Result<Vec<Self>>>()?)
+    }
+
+    ///Selects all the announcements of a contest in the SQLite database
+    pub fn select_all_by_contest(
+        contest_id: usize,
+        pool: &Pool<SqliteConnectionManager>,
+    ) -> Result<Vec<Self>, Box<dyn Error>> {
+        let conn = pool.get()?;
+        let mut stmt = conn.prepare("SELECT * from announcements WHERE contest_id = ?1")?;
+        let iter = stmt.query_map(params![contest_id], Self::from_row)?;
+        Ok(iter.collect::<rusqlite::Result<Vec<Self>>>()?)
+    }
+
+    ///Counts the announcements in the SQLite database
+    pub fn count(pool: &Pool<SqliteConnectionManager>) -> Result<usize, Box<dyn Error>> {
+        Ok(pool
+            .get()?
+            .query_row("SELECT COUNT(*) FROM announcements", params![], |row| {
+                row.get(0)
+            })?)
+    }
+
+    ///Reads an announcement from a row of the announcements table
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            contest_id: row.get(1)?,
+            content: row.get(2)?,
+            created_time: UtcDateTime {
+                time: match Utc.datetime_from_str(
+                    &match row.get::<_, String>(3) {
+                        Ok(s) => s,
+                        Err(_) => return Err(rusqlite::Error::InvalidQuery),
+                    },
+                    FORMAT,
+                ) {
+                    Ok(t) => t,
+                    Err(_) => return Err(rusqlite::Error::InvalidQuery),
+                },
+            },
+            author_id: row.get(4)?,
+        })
+    }
+}
@@ -0,0 +1,361 @@
+use actix_web::{http::StatusCode, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorReason {
+    ErrInvalidArgument,
+    ErrInvalidState,
+    ErrNotFound,
+    ErrRateLimit,
+    ErrExternal,
+    ErrInternal,
+    ErrForbidden,
+    ErrUnauthorized,
+}
+
+impl ErrorReason {
+    ///Gets the code in the response body
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::ErrInvalidArgument => 1,
+            Self::ErrInvalidState => 2,
+            Self::ErrNotFound => 3,
+            Self::ErrRateLimit => 4,
+            Self::ErrExternal => 5,
+            Self::ErrInternal => 6,
+            Self::ErrForbidden => 7,
+            Self::ErrUnauthorized => 8,
+        }
+    }
+
+    ///Gets the HTTP status of the response
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::ErrInvalidArgument | Self::ErrInvalidState | Self::ErrRateLimit => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::ErrNotFound => StatusCode::NOT_FOUND,
+            Self::ErrExternal | Self::ErrInternal => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::ErrForbidden => StatusCode::FORBIDDEN,
+            Self::ErrUnauthorized => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+///Body of response when errors occur
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ErrorResponseBody {
+    code: u16,
+    reason: ErrorReason,
+    message: String,
+}
+
+///Language of the messages in error responses
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Lang {
+    En,
+    Zh,
+}
+
+impl Lang {
+    ///Picks the most preferred supported language in the Accept-Language header
+    ///English is used if the header is missing or lists no supported language
+    pub fn of(req: &HttpRequest) -> Self {
+        let header = match req
+            .headers()
+            .get("Accept-Language")
+            .and_then(|value| value.to_str().ok())
+        {
+            Some(header) => header,
+            None => return Self::En,
+        };
+
+        let mut preferred = (Self::En, 0.0);
+        for entry in header.split(',') {
+            let mut parts = entry.trim().split(';');
+            let tag = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+            let weight = parts
+                .find_map(|part| part.trim().strip_prefix("q="))
+                .and_then(|weight| weight.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let lang = match tag.split('-').next().unwrap_or("") {
+                "en" => Self::En,
+                "zh" => Self::Zh,
+                _ => continue,
+            };
+            if weight > preferred.1 {
+                preferred = (lang, weight);
+            }
+        }
+        preferred.0
+    }
+}
+
+///Kinds of resources which may be missing
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Resource {
+    User,
+    Problem,
+    Contest,
+    Job,
+}
+
+impl Resource {
+    fn name(&self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Self::User, Lang::En) => "User",
+            (Self::Problem, Lang::En) => "Problem",
+            (Self::Contest, Lang::En) => "Contest",
+            (Self::Job, Lang::En) => "Job",
+            (Self::User, Lang::Zh) => "用户",
+            (Self::Problem, Lang::Zh) => "题目",
+            (Self::Contest, Lang::Zh) => "比赛",
+            (Self::Job, Lang::Zh) => "评测任务",
+        }
+    }
+}
+
+///Context of an error, turned into a message in the requested language
+#[derive(Clone, PartialEq, Debug)]
+pub enum Message {
+    Internal(String),
+    NotFound(Resource, usize),
+    LanguageNotSupported(String),
+    ///Language, problem id and the languages allowed
+    LanguageNotAllowed(String, usize, Vec<String>),
+    UserNameExists(String),
+    ///Contest id and problem id
+    ContestWithoutProblem(usize, usize),
+    ///Contest id and user id
+    ContestWithoutUser(usize, usize),
+    ContestNotOpen(usize),
+    SubmissionLimitReached,
+    ///User id and submissions allowed per minute
+    TooManySubmissions(usize, usize),
+    ///Problem id of a limit override outside the contest
+    OverrideWithoutProblem(usize),
+    ///Numbers of submission limits and problems
+    SubmissionLimitCount(usize, usize),
+    DynamicRankingRatioMissing(usize),
+    CannotAnnounce(usize),
+    JobNotFinished(usize),
+    JobNotRunning(usize),
+    ///Problem id and job id
+    ProblemChanged(usize, usize),
+    EmptyPassword,
+    NoPassword(usize),
+    TokenRequired(usize),
+    TokenOfOtherUser(usize),
+    InvalidToken(String),
+    WrongPassword(usize),
+    UnknownTimeZone(String),
+}
+
+impl Message {
+    ///Makes the message in the given language
+    pub fn text(&self, lang: Lang) -> String {
+        match lang {
+            Lang::En => self.en(),
+            Lang::Zh => self.zh(),
+        }
+    }
+
+    fn en(&self) -> String {
+        match self {
+            Self::Internal(e) => format!("Internal error: {}", e),
+            Self::NotFound(resource, id) => {
+                format!("{} {} not found.", resource.name(Lang::En), id)
+            }
+            Self::LanguageNotSupported(name) => format!("Language {} not supported.", name),
+            Self::LanguageNotAllowed(name, problem, allowed) => format!(
+                "Language {} not allowed in problem {}, allowed languages: {}.",
+                name,
+                problem,
+                allowed.join(", ")
+            ),
+            Self::UserNameExists(name) => format!("User name '{}' already exists.", name),
+            Self::ContestWithoutProblem(contest, problem) => {
+                format!("Contest {} does not contain problem {}.", contest, problem)
+            }
+            Self::ContestWithoutUser(contest, user) => {
+                format!("Contest {} does not contain user {}.", contest, user)
+            }
+            Self::ContestNotOpen(contest) => format!("Contest {} is not open now.", contest),
+            Self::SubmissionLimitReached => "Submission limit reached.".to_string(),
+            Self::TooManySubmissions(user, limit) => {
+                format!("User {} can only submit {} times per minute.", user, limit)
+            }
+            Self::OverrideWithoutProblem(problem) => {
+                format!("Contest does not contain problem {}.", problem)
+            }
+            Self::SubmissionLimitCount(limits, problems) => format!(
+                "{} submission limits given for {} problems.",
+                limits, problems
+            ),
+            Self::DynamicRankingRatioMissing(problem) => {
+                format!("Dynamic ranking ratio of problem {} not found.", problem)
+            }
+            Self::CannotAnnounce(user) => format!("User {} can not make announcements.", user),
+            Self::JobNotFinished(job) => format!("Job {} not finished.", job),
+            Self::JobNotRunning(job) => format!("Job {} not running.", job),
+            Self::ProblemChanged(problem, job) => format!(
+                "Problem {} has changed since job {} was judged, use force=true to rejudge anyway.",
+                problem, job
+            ),
+            Self::EmptyPassword => "Password can not be empty.".to_string(),
+            Self::NoPassword(user) => format!("User {} has no password.", user),
+            Self::TokenRequired(user) => format!("User {} requires a token.", user),
+            Self::TokenOfOtherUser(user) => format!("Token does not belong to user {}.", user),
+            Self::InvalidToken(e) => format!("Invalid token: {}", e),
+            Self::WrongPassword(user) => format!("Wrong password for user {}.", user),
+            Self::UnknownTimeZone(name) => format!("Time zone '{}' not found.", name),
+        }
+    }
+
+    fn zh(&self) -> String {
+        match self {
+            Self::Internal(e) => format!("内部错误：{}", e),
+            Self::NotFound(resource, id) => {
+                format!("{} {} 不存在。", resource.name(Lang::Zh), id)
+            }
+            Self::LanguageNotSupported(name) => format!("不支持语言 {}。", name),
+            Self::LanguageNotAllowed(name, problem, allowed) => format!(
+                "题目 {} 不允许使用语言 {}，允许的语言：{}。",
+                problem,
+                name,
+                allowed.join(", ")
+            ),
+            Self::UserNameExists(name) => format!("用户名 '{}' 已存在。", name),
+            Self::ContestWithoutProblem(contest, problem) => {
+                format!("比赛 {} 不包含题目 {}。", contest, problem)
+            }
+            Self::ContestWithoutUser(contest, user) => {
+                format!("比赛 {} 不包含用户 {}。", contest, user)
+            }
+            Self::ContestNotOpen(contest) => format!("比赛 {} 当前未开放。", contest),
+            Self::SubmissionLimitReached => "已达到提交次数上限。".to_string(),
+            Self::TooManySubmissions(user, limit) => {
+                format!("用户 {} 每分钟最多提交 {} 次。", user, limit)
+            }
+            Self::OverrideWithoutProblem(problem) => format!("比赛不包含题目 {}。", problem),
+            Self::SubmissionLimitCount(limits, problems) => {
+                format!(
+                    "给出了 {} 个提交次数上限，但比赛有 {} 道题目。",
+                    limits, problems
+                )
+            }
+            Self::DynamicRankingRatioMissing(problem) => {
+                format!("题目 {} 缺少竞争得分比例。", problem)
+            }
+            Self::CannotAnnounce(user) => format!("用户 {} 不能发布公告。", user),
+            Self::JobNotFinished(job) => format!("评测任务 {} 尚未完成。", job),
+            Self::JobNotRunning(job) => format!("评测任务 {} 不在评测中。", job),
+            Self::ProblemChanged(problem, job) => format!(
+                "题目 {} 在评测任务 {} 评测后已被修改，使用 force=true 强制重新评测。",
+                problem, job
+            ),
+            Self::EmptyPassword => "密码不能为空。".to_string(),
+            Self::NoPassword(user) => format!("用户 {} 未设置密码。", user),
+            Self::TokenRequired(user) => format!("用户 {} 需要令牌。", user),
+            Self::TokenOfOtherUser(user) => format!("令牌不属于用户 {}。", user),
+            Self::InvalidToken(e) => format!("无效的令牌：{}", e),
+            Self::WrongPassword(user) => format!("用户 {} 的密码错误。", user),
+            Self::UnknownTimeZone(name) => format!("时区 '{}' 不存在。", name),
+        }
+    }
+}
+
+///Error of a request, the code and the status are decided by its reason
+///Going over the rate limit of a user is the exception, which is 429 Too Many Requests
+#[derive(Clone, PartialEq, Debug)]
+pub struct ApiError {
+    pub reason: ErrorReason,
+    pub message: Message,
+}
+
+impl ApiError {
+    ///Gets the HTTP status of the response
+    pub fn status(&self) -> StatusCode {
+        match self.message {
+            Message::TooManySubmissions(..) => StatusCode::TOO_MANY_REQUESTS,
+            _ => self.reason.status(),
+        }
+    }
+
+    ///Makes the body of the error in the given language
+    pub fn body(&self, lang: Lang) -> ErrorResponseBody {
+        ErrorResponseBody {
+            code: self.reason.code(),
+            reason: self.reason,
+            message: self.message.text(lang),
+        }
+    }
+
+    ///Makes the response of the error in the language requested by the request
+    pub fn response(&self, req: &HttpRequest) -> HttpResponse {
+        self.response_in(Lang::of(req))
+    }
+
+    ///Makes the response of the error in the given language
+    pub fn response_in(&self, lang: Lang) -> HttpResponse {
+        HttpResponse::build(self.status()).body(serde_json::to_string(&self.body(lang)).unwrap())
+    }
+}
+
+//Shortcuts to make errors of each reason
+pub fn invalid_argument(message: Message) -> ApiError {
+    ApiError {
+        reason: ErrorReason::ErrInvalidArgument,
+        message,
+    }
+}
+
+pub fn invalid_state(message: Message) -> ApiError {
+    ApiError {
+        reason: ErrorReason::ErrInvalidState,
+        message,
+    }
+}
+
+pub fn not_found(resource: Resource, id: usize) -> ApiError {
+    ApiError {
+        reason: ErrorReason::ErrNotFound,
+        message: Message::NotFound(resource, id),
+    }
+}
+
+pub fn rate_limit(message: Message) -> ApiError {
+    ApiError {
+        reason: ErrorReason::ErrRateLimit,
+        message,
+    }
+}
+
+pub fn forbidden(message: Message) -> ApiError {
+    ApiError {
+        reason: ErrorReason::ErrForbidden,
+        message,
+    }
+}
+
+pub fn unauthorized(message: Message) -> ApiError {
+    ApiError {
+        reason: ErrorReason::ErrUnauthorized,
+        message,
+    }
+}
+
+pub fn internal(e: Box<dyn Error>) -> ApiError {
+    ApiError {
+        reason: ErrorReason::ErrInternal,
+        message: Message::Internal(e.to_string()),
+    }
+}
+
+///Shortcut to generate response for internal error
+///Used by oj_try, which has no request at hand, so the message is always in English
+pub fn internal_error(e: Box<dyn Error>) -> HttpResponse {
+    internal(e).response_in(Lang::En)
+}
@@ -0,0 +1,122 @@
+use std::{
+    io,
+    process::{Child, Command, ExitStatus},
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+
+///Limits the CPU time of the child process spawned by the command
+///The kernel sends SIGXCPU once the limit in microseconds (rounded up to seconds) is used up
+#[cfg(unix)]
+pub fn limit_cpu_time(command: &mut Command, cpu_time_limit: u64) {
+    if cpu_time_limit == 0 {
+        return;
+    }
+    let seconds = cpu_time_limit.div_ceil(1_000_000);
+    let limit = libc::rlimit {
+        rlim_cur: seconds as libc::rlim_t,
+        //The hard limit kills the child in case it ignores SIGXCPU
+        rlim_max: (seconds + 1) as libc::rlim_t,
+    };
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_CPU, &limit) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+///CPU time limits are not supported on this platform
+#[cfg(not(unix))]
+pub fn limit_cpu_time(_command: &mut Command, _cpu_time_limit: u64) {}
+
+///Limits the processes and threads the child process spawned by the command may create
+///RLIMIT_NPROC counts every process and thread of the user running the judge, the judge's own included,
+///and is not enforced for root, so the judge should run as an unprivileged user for it to take effect
+///A program going over the limit fails to fork or spawn threads, and is reported as a runtime error
+#[cfg(unix)]
+pub fn limit_processes(command: &mut Command, max_processes: u64) {
+    if max_processes == 0 {
+        return;
+    }
+    let limit = libc::rlimit {
+        rlim_cur: max_processes as libc::rlim_t,
+        rlim_max: max_processes as libc::rlim_t,
+    };
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_NPROC, &limit) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+///Process limits are not supported on this platform
+#[cfg(not(unix))]
+pub fn limit_processes(_command: &mut Command, _max_processes: u64) {}
+
+///Checks whether the child has exited without blocking
+///Returns: io::Result<Option<(ExitStatus, Option<Duration>)>> -- the exit status and the CPU time used by the child if it has exited
+#[cfg(unix)]
+pub fn try_wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+    let mut status = 0;
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    match unsafe {
+        libc::wait4(
+            child.id() as libc::pid_t,
+            &mut status,
+            libc::WNOHANG,
+            &mut usage,
+        )
+    } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(None),
+        _ => {
+            let cpu_time =
+                Duration::from_secs((usage.ru_utime.tv_sec + usage.ru_stime.tv_sec) as u64)
+                    + Duration::from_micros(
+                        (usage.ru_utime.tv_usec + usage.ru_stime.tv_usec) as u64,
+                    );
+            Ok(Some((ExitStatus::from_raw(status), Some(cpu_time))))
+        }
+    }
+}
+
+///Checks whether the child has exited without blocking
+///The CPU time is not available on this platform
+#[cfg(not(unix))]
+pub fn try_wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+    Ok(child.try_wait()?.map(|status| (status, None)))
+}
+
+///Checks whether the child was killed for exceeding its CPU time limit
+#[cfg(unix)]
+pub fn cpu_time_exceeded(
+    status: &ExitStatus,
+    cpu_time: Option<Duration>,
+    cpu_time_limit: u64,
+) -> bool {
+    match status.signal() {
+        Some(libc::SIGXCPU) => true,
+        Some(libc::SIGKILL) => {
+            cpu_time_limit != 0 && cpu_time.is_some_and(|t| t.as_micros() >= cpu_time_limit as u128)
+        }
+        _ => false,
+    }
+}
+
+///CPU time limits are not supported on this platform
+#[cfg(not(unix))]
+pub fn cpu_time_exceeded(
+    _status: &ExitStatus,
+    _cpu_time: Option<Duration>,
+    _cpu_time_limit: u64,
+) -> bool {
+    false
+}
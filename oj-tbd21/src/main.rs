@@ -1,27 +1,46 @@
+mod cache;
 mod database;
+mod errors;
+mod limits;
+mod queue;
+mod rate_limit;
 
 use actix_web::{
-    get, middleware::Logger, post, put, web, App, HttpResponse, HttpServer, Responder,
+    get,
+    middleware::{Compress, Logger},
+    post, put, web, App, HttpRequest, HttpResponse, HttpServer, Responder,
 };
+use auth::*;
+use cache::*;
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use clap::Parser;
 use database::*;
-use date_time_format::*;
 use env_logger;
+use errors::*;
+use limits::*;
 use log;
+use queue::*;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
+use rate_limit::*;
 use serde::{Deserialize, Serialize};
 use std::{
+    borrow::Cow,
     cmp::Ordering,
+    collections::BTreeMap,
     error::Error,
     fs,
     io::{self, Write},
     ops::{Deref, DerefMut},
     process::{Command, Stdio},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering as AtomicOrdering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
+use unicode_normalization::UnicodeNormalization;
 
 ///Tool macro to simplify error handling
 macro_rules! oj_try {
@@ -35,6 +54,9 @@ macro_rules! oj_try {
     };
 }
 
+//Declared after oj_try so that its handlers can use the macro
+mod auth;
+
 ///Module for formatting DateTime<Utc>
 mod date_time_format {
     use chrono::{DateTime, TimeZone, Utc};
@@ -42,6 +64,9 @@ mod date_time_format {
 
     pub const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
 
+    ///Format of the times shown in another time zone, with the offset in place of "Z"
+    pub const ZONED_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
+
     pub fn serialize<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -55,8 +80,13 @@ mod date_time_format {
         D: Deserializer<'d>,
     {
         let s = String::deserialize(deserializer)?;
-        Utc.datetime_from_str(&s, FORMAT)
-            .map_err(serde::de::Error::custom)
+        //Accepts any RFC 3339 timestamp and normalizes it to UTC
+        match DateTime::parse_from_rfc3339(&s) {
+            Ok(date) => Ok(date.with_timezone(&Utc)),
+            Err(_) => Utc
+                .datetime_from_str(&s, FORMAT)
+                .map_err(serde::de::Error::custom),
+        }
     }
 }
 
@@ -70,6 +100,12 @@ pub fn default_bind_port() -> u16 {
     12345
 }
 
+pub fn default_max_workers() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
 ///Server configuration
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Server {
@@ -78,6 +114,35 @@ pub struct Server {
 
     #[serde(default = "default_bind_port")]
     bind_port: u16,
+
+    #[serde(default = "default_max_workers")]
+    max_workers: usize,
+
+    ///Wall-clock budget in microseconds for judging a whole submission, 0 for unlimited
+    #[serde(default)]
+    max_total_judge_time: u64,
+
+    ///Limit on the processes and threads of the user running the judged programs, 0 for unlimited
+    ///See limits::limit_processes for what is counted
+    #[serde(default)]
+    max_processes: u64,
+
+    ///Submissions allowed for each user within any minute, across all problems and contests, 0 for unlimited
+    #[serde(default)]
+    max_submissions_per_minute: usize,
+
+    ///Secret used to sign login tokens
+    #[serde(default = "default_jwt_secret")]
+    jwt_secret: String,
+
+    ///Lifetime of login tokens in seconds
+    #[serde(default = "default_token_lifetime")]
+    token_lifetime: u64,
+
+    ///Whether users without a password may set the first one without a token, e.g. while setting up the server
+    ///Otherwise only the administrator (root) can set the first password of a user
+    #[serde(default)]
+    open_mode: bool,
 }
 
 #[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
@@ -132,10 +197,35 @@ pub enum OjResult {
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Case {
     score: f32,
+
+    ///Not read if input_generator is given
+    #[serde(default)]
     input_file: String,
+
+    ///Command printing the input of the case, run at judge time with a seed
+    ///The seed replaces "%SEED%" in the arguments, or is appended if there is none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    input_generator: Option<Vec<String>>,
+
     answer_file: String,
+
+    ///Other acceptable answers, the output is accepted if it matches any of them or answer_file
+    ///Ignored by special judges, which only get answer_file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    answer_files: Vec<String>,
+
     time_limit: u64,
     memory_limit: u64,
+
+    ///CPU time limit in microseconds, 0 for unlimited
+    ///Enforced on Unix only, the wall-clock time_limit still applies
+    #[serde(default)]
+    cpu_time_limit: u64,
+
+    ///Judged like the other cases, but its files are also shown by GET /problems/{problemId}/samples
+    ///Left out of the configuration hash when false, so existing problems keep their version
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    sample: bool,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -145,6 +235,10 @@ pub struct CaseResult {
     time: u128,
     memory: u128,
     info: String,
+
+    ///CPU time in microseconds, if measured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cpu_time: Option<u128>,
 }
 
 ///Miscellaneous configuration
@@ -152,6 +246,92 @@ pub struct CaseResult {
 pub struct Misc {
     special_judge: Option<Vec<String>>,
     dynamic_ranking_ratio: Option<f32>,
+
+    ///Stops judging at the first case not accepted
+    #[serde(default)]
+    fail_fast: bool,
+
+    ///Unifies the line endings before a strict comparison
+    #[serde(default = "default_normalize_newlines")]
+    normalize_newlines: bool,
+
+    ///Notes in the info of a wrong answer whether the output only differs in whitespace
+    #[serde(default = "default_report_whitespace")]
+    report_whitespace: bool,
+
+    ///Compares the raw bytes of the output and the answer, for problems printing binary data
+    ///Special judges are still given the output file as is
+    #[serde(default)]
+    binary_output: bool,
+
+    ///Names of the only languages accepted, all the configured languages if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    allowed_languages: Option<Vec<String>>,
+
+    ///Overrides max_processes of the server for this problem, 0 for unlimited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_processes: Option<u64>,
+
+    ///Compares the output and the answer in Unicode normalization form C,
+    ///so that canonically equivalent text, e.g. a precomposed letter and its combining sequence, matches
+    #[serde(default)]
+    unicode_output: bool,
+
+    ///Groups of cases scored together, each case not in a group is scored on its own
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<CaseGroup>,
+}
+
+///Cases scored together, see GroupScoring
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CaseGroup {
+    ///Ids of the cases, starting from 1
+    cases: Vec<usize>,
+
+    #[serde(default)]
+    group_scoring: GroupScoring,
+}
+
+///How the score of a group is computed from the results of its cases
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupScoring {
+    ///The scores of the accepted cases are added up, as if there were no group
+    #[default]
+    Sum,
+    ///The scores of the group are only earned if every case is accepted
+    AllOrNothing,
+}
+
+pub fn default_normalize_newlines() -> bool {
+    true
+}
+
+pub fn default_report_whitespace() -> bool {
+    true
+}
+
+///Makes the info of a wrong answer, which is the output
+///A note is appended if the output matches an answer once runs of whitespace are collapsed and trimmed
+fn wrong_answer_info(stdout: String, answers: &[String], misc: &Misc) -> String {
+    if misc.report_whitespace
+        && answers
+            .iter()
+            .any(|answer| stdout.split_whitespace().eq(answer.split_whitespace()))
+    {
+        format!("{}\n(differs only in whitespace)", stdout)
+    } else {
+        stdout
+    }
+}
+
+///Puts the text in Unicode normalization form C if the problem compares Unicode output
+fn normalize_unicode<'a>(text: &'a str, misc: &Misc) -> Cow<'a, str> {
+    if misc.unicode_output {
+        Cow::Owned(text.nfc().collect())
+    } else {
+        Cow::Borrowed(text)
+    }
 }
 
 ///Problem configuration
@@ -164,14 +344,183 @@ pub struct Problem {
     problem_type: ProblemType,
     misc: Misc,
     cases: Vec<Case>,
+
+    ///Publicly visible example cases, never judged
+    #[serde(default)]
+    sample_cases: Vec<Case>,
+}
+
+impl Problem {
+    ///Gets the cases shown to contestants, the example cases followed by the judged ones marked as samples
+    fn public_cases(&self) -> impl Iterator<Item = &Case> {
+        self.sample_cases
+            .iter()
+            .chain(self.cases.iter().filter(|case| case.sample))
+    }
+
+    ///Gets the score earned by the results of the cases, which start from id 1
+    fn score(&self, case_results: &[CaseResult]) -> f32 {
+        //The part of its score earned by a case, where partial credit would be given
+        let ratio = |id: usize| match case_results.get(id).map(|case| case.result) {
+            Some(OjResult::Accepted) => 1.0,
+            _ => 0.0,
+        };
+        let case_score = |id: &usize| self.cases[id - 1].score;
+
+        let grouped = self
+            .misc
+            .groups
+            .iter()
+            .flat_map(|group| &group.cases)
+            .collect::<Vec<_>>();
+        let ungrouped: f32 = (1..=self.cases.len())
+            .filter(|id| !grouped.contains(&id))
+            .map(|id| case_score(&id) * ratio(id))
+            .sum();
+        let groups: f32 = self
+            .misc
+            .groups
+            .iter()
+            .map(|group| {
+                let total: f32 = group.cases.iter().map(case_score).sum();
+                match group.group_scoring {
+                    GroupScoring::Sum => group
+                        .cases
+                        .iter()
+                        .map(|id| case_score(id) * ratio(*id))
+                        .sum(),
+                    GroupScoring::AllOrNothing => {
+                        match group.cases.iter().all(|id| ratio(*id) == 1.0) {
+                            true => total,
+                            false => 0.0,
+                        }
+                    }
+                }
+            })
+            .sum();
+        ungrouped + groups
+    }
+
+    ///Gets a hash of the configuration, used to tell whether the problem has changed
+    ///FNV-1a is used since it is stable across runs and Rust versions
+    fn config_version(&self) -> String {
+        let hash = serde_json::to_string(self)
+            .unwrap()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        format!("{:016x}", hash)
+    }
+}
+
+///Body of problem response, the judging cases are not revealed
+#[derive(Serialize, Clone, Debug)]
+pub struct ProblemStatement<'a> {
+    id: usize,
+    name: &'a str,
+
+    #[serde(rename = "type")]
+    problem_type: ProblemType,
+    sample_cases: &'a [Case],
+}
+
+///Body of sample case response
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Sample {
+    input: String,
+    output: String,
+}
+
+impl Sample {
+    ///Reads the input and answer files of a sample case
+    fn read(case: &Case) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            input: fs::read_to_string(&case.input_file)?,
+            output: fs::read_to_string(&case.answer_file)?,
+        })
+    }
 }
 
 ///Language configuration
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Language {
     name: String,
+
+    ///Name of the source file, "%CLASS%" in it is replaced by the main class of the source
     file_name: String,
+
+    ///Placeholders "%INPUT%", "%OUTPUT%", "%DIR%" and "%CLASS%" may appear anywhere in an argument
     command: Vec<String>,
+
+    ///Distinguishes entries sharing the same name, e.g. "GCC 12" for "C++ (GCC 12)"
+    #[serde(default)]
+    language_version: Option<String>,
+}
+
+impl Language {
+    ///Gets the name used by submissions to select this language
+    fn full_name(&self) -> String {
+        match self.language_version {
+            Some(ref version) => format!("{} ({})", self.name, version),
+            None => self.name.clone(),
+        }
+    }
+
+    ///Finds the first problem in the configuration of the language, if any
+    fn check(&self) -> Option<String> {
+        if self.command.is_empty() {
+            return Some(format!("Language {} has no command", self.full_name()));
+        }
+        if self.file_name.is_empty() || self.file_name.contains('/') {
+            return Some(format!(
+                "Language {} has invalid file name {}",
+                self.full_name(),
+                self.file_name
+            ));
+        }
+        for placeholder in ["%INPUT%", "%OUTPUT%"] {
+            if !self.command.iter().any(|arg| arg.contains(placeholder)) {
+                return Some(format!(
+                    "Command of language {} does not reference {}",
+                    self.full_name(),
+                    placeholder
+                ));
+            }
+        }
+        None
+    }
+
+    ///Gets the name of the source file for the given source code
+    fn source_file_name(&self, source_code: &str) -> String {
+        self.file_name
+            .replace("%CLASS%", &main_class_name(source_code))
+    }
+}
+
+///Finds the name of the first public class in the source code, "Main" if there is none
+fn main_class_name(source_code: &str) -> String {
+    let words = source_code.split_whitespace().collect::<Vec<_>>();
+    for (i, word) in words.iter().enumerate() {
+        if *word != "public" {
+            continue;
+        }
+        //Skips modifiers between "public" and "class"
+        let mut j = i + 1;
+        while j < words.len() && ["final", "abstract", "static"].contains(&words[j]) {
+            j += 1;
+        }
+        if j + 1 < words.len() && words[j] == "class" {
+            let name = words[j + 1]
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+                .collect::<String>();
+            if !name.is_empty() {
+                return name;
+            }
+        }
+    }
+    "Main".to_string()
 }
 
 ///Overall configuration
@@ -182,37 +531,6 @@ pub struct Config {
     languages: Vec<Language>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum ErrorReason {
-    ErrInvalidArgument,
-    ErrInvalidState,
-    ErrNotFound,
-    ErrRateLimit,
-    ErrExternal,
-    ErrInternal,
-}
-
-///Body of response when errors occur
-#[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct ErrorResponseBody {
-    code: u16,
-    reason: ErrorReason,
-    message: String,
-}
-
-///Shortcut to generate response for internal error
-fn internal_error(e: Box<dyn Error>) -> HttpResponse {
-    HttpResponse::InternalServerError().body(
-        serde_json::to_string(&ErrorResponseBody {
-            code: 6,
-            reason: ErrorReason::ErrInternal,
-            message: format!("Internal error: {}", e.to_string()),
-        })
-        .unwrap(),
-    )
-}
-
 ///Body of submission response
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Submission {
@@ -235,6 +553,48 @@ pub struct Job {
     result: OjResult,
     score: f32,
     cases: Vec<CaseResult>,
+
+    ///Configured compile command of the language when judged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language_command: Option<Vec<String>>,
+
+    ///Version of the problem configuration the job is judged against
+    #[serde(default)]
+    config_version: String,
+}
+
+impl Job {
+    ///Makes a new Job waiting in the judge queue
+    fn queueing(
+        id: usize,
+        submission: &Submission,
+        case_count: usize,
+        config_version: String,
+        created_time: UtcDateTime,
+        updated_time: UtcDateTime,
+    ) -> Self {
+        Self {
+            id,
+            created_time,
+            updated_time,
+            submission: submission.clone(),
+            state: OjState::Queueing,
+            result: OjResult::Waiting,
+            score: 0.0,
+            cases: (0..=case_count)
+                .map(|i| CaseResult {
+                    id: i,
+                    result: OjResult::Waiting,
+                    time: 0,
+                    memory: 0,
+                    info: "".to_string(),
+                    cpu_time: None,
+                })
+                .collect(),
+            language_command: None,
+            config_version,
+        }
+    }
 }
 
 ///Information and configuration of a contest
@@ -247,6 +607,73 @@ pub struct Contest {
     problem_ids: Vec<usize>,
     user_ids: Vec<usize>,
     submission_limit: usize,
+
+    #[serde(default)]
+    limit_overrides: Vec<LimitOverride>,
+
+    ///Submission limits of each problem, parallel to problem_ids
+    #[serde(default)]
+    per_problem_submission_limit: Option<Vec<usize>>,
+}
+
+impl Contest {
+    ///Gets the submission limit of a problem in the contest
+    fn submission_limit_of(&self, problem_id: usize) -> usize {
+        match (
+            &self.per_problem_submission_limit,
+            self.problem_ids.iter().position(|id| *id == problem_id),
+        ) {
+            (Some(limits), Some(index)) => limits[index],
+            _ => self.submission_limit,
+        }
+    }
+}
+
+///Overrides the case limits of a problem inside a contest
+///Absolute limits take precedence over multipliers
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct LimitOverride {
+    problem_id: usize,
+    time_limit: Option<u64>,
+    memory_limit: Option<u64>,
+    time_limit_multiplier: Option<f64>,
+    memory_limit_multiplier: Option<f64>,
+}
+
+impl LimitOverride {
+    ///Makes a copy of the case with the overridden limits
+    fn apply(&self, case: &Case) -> Case {
+        Case {
+            time_limit: match (self.time_limit, self.time_limit_multiplier) {
+                (Some(limit), _) => limit,
+                (None, Some(multiplier)) => (case.time_limit as f64 * multiplier) as u64,
+                (None, None) => case.time_limit,
+            },
+            memory_limit: match (self.memory_limit, self.memory_limit_multiplier) {
+                (Some(limit), _) => limit,
+                (None, Some(multiplier)) => (case.memory_limit as f64 * multiplier) as u64,
+                (None, None) => case.memory_limit,
+            },
+            ..case.clone()
+        }
+    }
+}
+
+///Announcement broadcast to the participants of a contest
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Announcement {
+    id: usize,
+    contest_id: usize,
+    content: String,
+    created_time: UtcDateTime,
+    author_id: usize,
+}
+
+///Body of announcement request
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct AnnouncementRequest {
+    content: String,
+    author_id: usize,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -254,6 +681,10 @@ pub struct User {
     #[serde(default)]
     id: Option<usize>,
     name: String,
+
+    ///Bcrypt hash of the password, users without one need no login
+    #[serde(skip)]
+    password_hash: Option<String>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -401,6 +832,102 @@ impl Filter {
     }
 }
 
+///Case result filter
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct CaseFilter {
+    result: Option<OjResult>,
+}
+
+///User filter
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct UserFilter {
+    ///Substring of the user name
+    name: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+///Filter of the problems solved by a user
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct SolvedFilter {
+    ///Only counts the jobs submitted in the contest, or outside any contest
+    contest_id: Option<usize>,
+}
+
+///Problem solved by a user, with the earliest accepted job
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SolvedProblem {
+    problem_id: usize,
+    job_id: usize,
+    time: UtcDateTime,
+}
+
+///Options of submitting
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct SubmitOptions {
+    ///Only compiles the source code, every case is skipped
+    #[serde(default)]
+    compile_only: bool,
+}
+
+///Options of rejudging
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct RejudgeOptions {
+    ///Rejudges even if the problem has changed since the job was judged
+    #[serde(default)]
+    force: bool,
+}
+
+///Difference of a case between a stored job and its verification run
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CaseDiff {
+    id: usize,
+    original_result: OjResult,
+    result: OjResult,
+
+    ///Time of the verification run minus the stored time, in microseconds
+    time_delta: i128,
+}
+
+///Report of re-running a job without storing the result
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct VerifyReport {
+    job_id: usize,
+    original_result: OjResult,
+    result: OjResult,
+    result_changed: bool,
+    original_score: f32,
+    score: f32,
+    score_matches: bool,
+    cases: Vec<CaseDiff>,
+}
+
+impl VerifyReport {
+    ///Compares the stored job with the job judged again
+    fn compare(original: &Job, verified: &Job) -> Self {
+        Self {
+            job_id: original.id,
+            original_result: original.result,
+            result: verified.result,
+            result_changed: original.result != verified.result,
+            original_score: original.score,
+            score: verified.score,
+            score_matches: original.score == verified.score,
+            cases: original
+                .cases
+                .iter()
+                .zip(&verified.cases)
+                .map(|(original, verified)| CaseDiff {
+                    id: original.id,
+                    original_result: original.result,
+                    result: verified.result,
+                    time_delta: verified.time as i128 - original.time as i128,
+                })
+                .collect(),
+        }
+    }
+}
+
 ///Wrapped DateTime<Utc> for the convenience of serialization and deserialization
 #[derive(Deserialize, Serialize, Clone, Copy, Debug)]
 #[serde(transparent)]
@@ -425,14 +952,26 @@ impl DerefMut for UtcDateTime {
     }
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Copy)]
+#[derive(Deserialize, Serialize, Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "snake_case")]
 pub enum ScoringRule {
     Latest,
     Highest,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Copy)]
+impl ScoringRule {
+    ///Selects the valid job among the jobs according to the scoring rule
+    fn select<'a>(&self, jobs: &'a [Job]) -> Option<&'a Job> {
+        match self {
+            ScoringRule::Latest => jobs.iter().max_by_key(|job| *job.created_time),
+            ScoringRule::Highest => jobs
+                .iter()
+                .max_by(|l, r| l.score.partial_cmp(&r.score).unwrap()),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "snake_case")]
 pub enum TieBreaker {
     SubmissionTime,
@@ -440,10 +979,136 @@ pub enum TieBreaker {
     UserId,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Copy)]
+#[derive(Deserialize, Serialize, Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct RankingRule {
     scoring_rule: Option<ScoringRule>,
     tie_breaker: Option<TieBreaker>,
+
+    ///Drops the users without any submission from the ranklist
+    #[serde(default)]
+    hide_inactive: bool,
+}
+
+///Format of a ranklist response
+#[derive(Deserialize, Serialize, Clone, Debug, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum RanklistFormat {
+    #[default]
+    Json,
+    ///A spreadsheet of the ranks, the total scores and the score of each problem
+    Csv,
+}
+
+impl RanklistFormat {
+    ///Renders the ranked users, whose scores are in the order of the problem ids
+    fn render(&self, usersranking: &[UsersRanking], problem_ids: &[usize]) -> String {
+        match self {
+            Self::Json => serde_json::to_string(usersranking).unwrap(),
+            Self::Csv => {
+                let mut csv = "rank,user_id,user_name,total_score".to_string();
+                for problem_id in problem_ids {
+                    csv += &format!(",problem_{}", problem_id);
+                }
+                csv.push('\n');
+                for ranking in usersranking {
+                    csv += &format!(
+                        "{},{},{},{}",
+                        ranking.rank,
+                        ranking.user.id.unwrap(),
+                        csv_field(&ranking.user.name),
+                        ranking.scores.iter().sum::<f32>()
+                    );
+                    for score in &ranking.scores {
+                        csv += &format!(",{}", score);
+                    }
+                    csv.push('\n');
+                }
+                csv
+            }
+        }
+    }
+
+    ///Makes the response of a rendered ranklist
+    fn response(&self, ranklist: String) -> HttpResponse {
+        match self {
+            Self::Json => HttpResponse::Ok().body(ranklist),
+            Self::Csv => HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .body(ranklist),
+        }
+    }
+}
+
+///Quotes a CSV field if it contains a separator, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+///Query of the ranklist besides the ranking rule
+#[derive(Deserialize, Serialize, Clone, Debug, Copy)]
+pub struct RanklistQuery {
+    ///Computes the ranklist again even if it is cached
+    #[serde(default)]
+    refresh: bool,
+
+    #[serde(default)]
+    format: RanklistFormat,
+}
+
+///Query of the time zone to show the times of the jobs in
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct TimeZoneQuery {
+    ///IANA name of the time zone, e.g. "Asia/Shanghai", the times stay in UTC if not given
+    tz: Option<String>,
+}
+
+impl TimeZoneQuery {
+    ///Looks up the time zone by its name
+    ///Returns: Result<Option<Tz>, Message> -- None if not given, an error if the name is unknown
+    fn time_zone(&self) -> Result<Option<Tz>, Message> {
+        match &self.tz {
+            Some(name) => name
+                .parse::<Tz>()
+                .map(Some)
+                .map_err(|_| Message::UnknownTimeZone(name.clone())),
+            None => Ok(None),
+        }
+    }
+}
+
+///Serializes a job or a list of jobs, with the times in the time zone if given
+///The stored times are always in UTC
+fn jobs_in_time_zone<T: Serialize>(jobs: &T, tz: Option<Tz>) -> String {
+    let json = serde_json::to_string(jobs).unwrap();
+    let Some(tz) = tz else {
+        return json;
+    };
+
+    //The times are changed in the JSON, as a serde_json::Value can not hold the u128 fields of a job
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let convert = |job: &mut serde_json::Value| {
+        for key in ["created_time", "updated_time"] {
+            if let Some(time) = job[key]
+                .as_str()
+                .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            {
+                job[key] = serde_json::Value::String(
+                    time.with_timezone(&tz)
+                        .format(date_time_format::ZONED_FORMAT)
+                        .to_string(),
+                );
+            }
+        }
+    };
+    match value.as_array_mut() {
+        Some(jobs) => jobs.iter_mut().for_each(convert),
+        None => convert(&mut value),
+    }
+    value.to_string()
 }
 
 ///Command-line arguments
@@ -460,41 +1125,54 @@ pub struct Cli {
 
     #[clap(short, long = "flush-data", action)]
     flush_data: bool,
+
+    ///Dumps the jobs, users and contests to a JSON file and exits
+    #[clap(long, value_parser)]
+    dump: Option<String>,
+
+    ///Restores the jobs, users and contests from a JSON file made by --dump and exits
+    #[clap(long, value_parser, conflicts_with = "dump")]
+    restore: Option<String>,
+
+    ///Refuses to start if the case scores of any problem do not sum to 100
+    #[clap(long = "strict-config", action)]
+    strict_config: bool,
 }
 
-///POST requests for "/jobs" handler
-#[post("/jobs")]
-async fn post_jobs(
-    submission: web::Json<Submission>,
-    config: web::Data<Config>,
-    pool: web::Data<Pool<SqliteConnectionManager>>,
-) -> impl Responder {
-    log::info!(target: "post_jobs_handler", "Handling POST for problem {} in contest {}", submission.problem_id, submission.contest_id);
+///Response of a submission, the job with the number of submissions left in its contest
+#[derive(Serialize, Clone, Debug)]
+pub struct SubmittedJob {
+    #[serde(flatten)]
+    job: Job,
 
-    let created_time = UtcDateTime { time: Utc::now() };
+    ///Not given for submissions outside contests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remaining_submissions: Option<usize>,
+}
 
-    //Unwraps the arguments
-    let submission = submission.into_inner();
-    let pool = pool.into_inner();
-    let config = config.into_inner();
+///Checks a submission, stores it as a queueing job and puts it into the judge queue
+fn submit_job(
+    req: &HttpRequest,
+    submission: Submission,
+    config: &Arc<Config>,
+    pool: &Arc<Pool<SqliteConnectionManager>>,
+    queue: &Arc<JudgeQueue>,
+    compile_only: bool,
+) -> Result<SubmittedJob, ApiError> {
+    let created_time = UtcDateTime { time: Utc::now() };
 
     //Checks the request
-
     if !config
         .languages
         .iter()
-        .map(|lang| &lang.name)
+        .map(|lang| lang.full_name())
         .collect::<Vec<_>>()
-        .contains(&&submission.language)
+        .contains(&submission.language)
     {
-        return HttpResponse::NotFound().body(
-            serde_json::to_string(&ErrorResponseBody {
-                code: 3,
-                reason: ErrorReason::ErrNotFound,
-                message: format!("Language {} not supported.", submission.language),
-            })
-            .unwrap(),
-        );
+        return Err(ApiError {
+            reason: ErrorReason::ErrNotFound,
+            message: Message::LanguageNotSupported(submission.language.clone()),
+        });
     }
 
     if !config
@@ -504,136 +1182,265 @@ async fn post_jobs(
         .collect::<Vec<_>>()
         .contains(&submission.problem_id)
     {
-        return HttpResponse::NotFound().body(
-            serde_json::to_string(&ErrorResponseBody {
-                code: 3,
-                reason: ErrorReason::ErrNotFound,
-                message: format!("Problem {} not found.", submission.problem_id),
-            })
-            .unwrap(),
-        );
+        return Err(not_found(Resource::Problem, submission.problem_id));
+    }
+
+    //Some problems only accept certain languages
+    let problem = config
+        .problems
+        .iter()
+        .find(|problem| problem.id == submission.problem_id)
+        .unwrap();
+    if let Some(ref allowed) = problem.misc.allowed_languages {
+        if !allowed.contains(&submission.language) {
+            return Err(invalid_argument(Message::LanguageNotAllowed(
+                submission.language.clone(),
+                problem.id,
+                allowed.clone(),
+            )));
+        }
     }
 
     //Contest-related checks
-    match oj_try!(Contest::select_by_id(submission.contest_id, &pool)) {
-        Some(contest) => {
+    let mut remaining_submissions = None;
+    let contest = Contest::select_by_id(submission.contest_id, pool).map_err(internal)?;
+    match contest {
+        Some(ref contest) => {
             if !contest.problem_ids.contains(&submission.problem_id) {
-                return HttpResponse::BadRequest().body(
-                    serde_json::to_string(&ErrorResponseBody {
-                        code: 1,
-                        reason: ErrorReason::ErrInvalidArgument,
-                        message: format!(
-                            "Contest {} does not contains problem {}.",
-                            contest.id.unwrap(),
-                            submission.problem_id
-                        ),
-                    })
-                    .unwrap(),
-                );
+                return Err(invalid_argument(Message::ContestWithoutProblem(
+                    contest.id.unwrap(),
+                    submission.problem_id,
+                )));
             }
             if !contest.user_ids.contains(&submission.user_id) {
-                return HttpResponse::BadRequest().body(
-                    serde_json::to_string(&ErrorResponseBody {
-                        code: 1,
-                        reason: ErrorReason::ErrInvalidArgument,
-                        message: format!(
-                            "Contest {} does not contains user {}.",
-                            contest.id.unwrap(),
-                            submission.user_id
-                        ),
-                    })
-                    .unwrap(),
-                );
+                return Err(invalid_argument(Message::ContestWithoutUser(
+                    contest.id.unwrap(),
+                    submission.user_id,
+                )));
             }
             if *created_time < *contest.from || *created_time > *contest.to {
-                return HttpResponse::BadRequest().body(
-                    serde_json::to_string(&ErrorResponseBody {
-                        code: 1,
-                        reason: ErrorReason::ErrInvalidArgument,
-                        message: format!("Contest {} is not open now", contest.id.unwrap()),
-                    })
-                    .unwrap(),
-                );
+                return Err(invalid_argument(Message::ContestNotOpen(
+                    contest.id.unwrap(),
+                )));
             }
-            if {
-                oj_try!(Filter {
-                    user_id: Some(submission.user_id),
-                    contest_id: Some(contest.id.unwrap()),
-                    problem_id: Some(submission.problem_id),
-                    ..Default::default()
-                }
-                .apply(&pool))
-                .len()
-                    == contest.submission_limit
-            } {
-                return HttpResponse::BadRequest().body(
-                    serde_json::to_string(&ErrorResponseBody {
-                        code: 4,
-                        reason: ErrorReason::ErrRateLimit,
-                        message: format!("Submission limit reached"),
-                    })
-                    .unwrap(),
-                );
+            let submitted = Filter {
+                user_id: Some(submission.user_id),
+                contest_id: Some(contest.id.unwrap()),
+                problem_id: Some(submission.problem_id),
+                ..Default::default()
+            }
+            .apply(pool)
+            .map_err(internal)?
+            .len();
+            let limit = contest.submission_limit_of(submission.problem_id);
+            if submitted >= limit {
+                return Err(rate_limit(Message::SubmissionLimitReached));
             }
+            remaining_submissions = Some(limit - submitted - 1);
         }
         None => {
             if submission.contest_id != 0 {
-                return HttpResponse::NotFound().body(
-                    serde_json::to_string(&ErrorResponseBody {
-                        code: 3,
-                        reason: ErrorReason::ErrNotFound,
-                        message: format!("Contest {} not found.", submission.contest_id),
-                    })
-                    .unwrap(),
-                );
+                return Err(not_found(Resource::Contest, submission.contest_id));
             }
         }
     }
 
-    match oj_try!(User::select_by_id(submission.user_id, &pool)) {
-        Some(_) => {}
-        None => {
-            return HttpResponse::NotFound().body(
-                serde_json::to_string(&ErrorResponseBody {
-                    code: 3,
-                    reason: ErrorReason::ErrNotFound,
-                    message: format!("User {} not found.", submission.user_id),
-                })
-                .unwrap(),
-            );
+    match User::select_by_id(submission.user_id, pool).map_err(internal)? {
+        Some(user) => {
+            //Submitting on behalf of a user with a password requires the token
+            authorize(req, &user, config)?;
+        }
+        None => {
+            return Err(not_found(Resource::User, submission.user_id));
+        }
+    }
+
+    //Only the submissions passing every other check count against the rate limit
+    let limit = config.server.max_submissions_per_minute;
+    if limit != 0
+        && !SUBMISSION_RATES.try_submit(submission.user_id, limit, Duration::from_secs(60))
+    {
+        return Err(rate_limit(Message::TooManySubmissions(
+            submission.user_id,
+            limit,
+        )));
+    }
+
+    //Creates the job
+    let mut job = Job::queueing(
+        0,
+        &submission,
+        problem.cases.len(),
+        problem.config_version(),
+        created_time,
+        created_time,
+    );
+
+    //Stores to the SQLite database and waits for judging
+    job.id = job.insert(pool).map_err(internal)?;
+    queue.clone().push(
+        job.clone(),
+        config.clone(),
+        contest,
+        pool.clone(),
+        compile_only,
+    );
+
+    Ok(SubmittedJob {
+        job,
+        remaining_submissions,
+    })
+}
+
+///POST requests for "/jobs" handler
+#[post("/jobs")]
+async fn post_jobs(
+    req: HttpRequest,
+    submission: web::Json<Submission>,
+    options: web::Query<SubmitOptions>,
+    config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+    queue: web::Data<JudgeQueue>,
+) -> impl Responder {
+    log::info!(target: "post_jobs_handler", "Handling POST for problem {} in contest {}", submission.problem_id, submission.contest_id);
+
+    match submit_job(
+        &req,
+        submission.into_inner(),
+        &config.into_inner(),
+        &pool.into_inner(),
+        &queue.into_inner(),
+        options.compile_only,
+    ) {
+        Ok(submitted) => HttpResponse::Ok().body(serde_json::to_string(&submitted).unwrap()),
+        Err(e) => e.response(&req),
+    }
+}
+
+///Result of a submission in a batch
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct BatchResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    job_id: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorResponseBody>,
+}
+
+///POST requests for "/jobs/batch" handler
+///Each submission is checked on its own, so a failed one does not affect the others
+#[post("/jobs/batch")]
+async fn post_jobs_batch(
+    req: HttpRequest,
+    submissions: web::Json<Vec<Submission>>,
+    config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+    queue: web::Data<JudgeQueue>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let submissions = submissions.into_inner();
+    let config = config.into_inner();
+    let pool = pool.into_inner();
+    let queue = queue.into_inner();
+
+    log::info!(target: "post_jobs_batch_handler", "Handling POST for {} submissions", submissions.len());
+
+    let lang = Lang::of(&req);
+    let results = submissions
+        .into_iter()
+        .map(
+            |submission| match submit_job(&req, submission, &config, &pool, &queue, false) {
+                Ok(submitted) => BatchResult {
+                    job_id: Some(submitted.job.id),
+                    error: None,
+                },
+                Err(e) => BatchResult {
+                    job_id: None,
+                    error: Some(e.body(lang)),
+                },
+            },
+        )
+        .collect::<Vec<_>>();
+
+    HttpResponse::Ok().body(serde_json::to_string(&results).unwrap())
+}
+
+///Number of judge runs started, used to name their temporary directories
+static JUDGE_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+///Runs the input generator of a case and opens the input it printed
+///The seed only depends on the job and the case, so judging the job again feeds the same input
+///The generator is killed once it runs longer than the time limit, if any, or once the job is asked to be killed
+fn generate_input(
+    generator: &[String],
+    job_id: usize,
+    case_id: usize,
+    temp_dir: &str,
+    time_limit: Option<Duration>,
+) -> Result<fs::File, Box<dyn Error>> {
+    let seed = (job_id as u64) << 16 | case_id as u64;
+    let mut args = generator[1..]
+        .iter()
+        .map(|arg| arg.replace("%SEED%", &seed.to_string()))
+        .collect::<Vec<_>>();
+    if !generator.iter().any(|arg| arg.contains("%SEED%")) {
+        args.push(seed.to_string());
+    }
+
+    let input_path = format!("{}/{}", temp_dir, "input");
+    let generate_instant = Instant::now();
+    let mut generator_child = Command::new(&generator[0])
+        .args(&args)
+        .stdout(Stdio::from(fs::File::create(&input_path)?))
+        .stderr(Stdio::piped())
+        .spawn()?;
+    loop {
+        check_killed(job_id, &mut generator_child, temp_dir)?;
+        if generator_child.try_wait()?.is_some() {
+            break;
+        }
+        if time_limit.is_some_and(|limit| generate_instant.elapsed() > limit) {
+            generator_child.kill()?;
+            generator_child.wait()?;
+            return Err("timed out".into());
         }
     }
 
-    //Does judging
-    let job = oj_try!(judge(
-        oj_try!(Job::count(&pool)),
-        &submission,
-        config.clone(),
-        created_time,
-        created_time,
-    ));
-
-    //Stores to the SQLite database
-    oj_try!(job.insert(&pool));
-
-    HttpResponse::Ok().body(serde_json::to_string(&job).unwrap())
+    let output = generator_child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "{}, {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(fs::File::open(&input_path)?)
 }
 
 ///Judges the submission and create a new Job record
+///The limits of the cases may be overridden by the contest the submission belongs to
+///If compile_only is set, the judging stops after the compilation and every case is skipped
 fn judge(
     id: usize,
     submission: &Submission,
     config: Arc<Config>,
+    contest: Option<&Contest>,
     created_time: UtcDateTime,
     updated_time: UtcDateTime,
+    compile_only: bool,
 ) -> Result<Job, Box<dyn Error>> {
     //Initializes required variables
+    let judge_instant = Instant::now();
+    let total_budget = Duration::from_micros(config.server.max_total_judge_time);
+    let budget_exceeded =
+        || config.server.max_total_judge_time != 0 && judge_instant.elapsed() > total_budget;
     let mut score = 0.0;
     let mut result = OjResult::Accepted;
     let language = config
         .languages
         .iter()
-        .filter(|language| language.name == submission.language)
+        .filter(|language| language.full_name() == submission.language)
         .next()
         .unwrap();
     let problem = config
@@ -643,20 +1450,42 @@ fn judge(
         .unwrap();
     let mut case_results = vec![];
 
+    //Applies the limit overrides of the contest if any
+    let cases = match contest.and_then(|contest| {
+        contest
+            .limit_overrides
+            .iter()
+            .find(|o| o.problem_id == problem.id)
+    }) {
+        Some(limit_override) => problem
+            .cases
+            .iter()
+            .map(|case| limit_override.apply(case))
+            .collect::<Vec<_>>(),
+        None => problem.cases.clone(),
+    };
+
     //Prepare the file system ready for the following steps
-    let temp_dir = format!("temp/{}", created_time.format(FORMAT).to_string());
+    //Every run gets its own directory so that verifying a job does not clash with judging it
+    let temp_dir = format!(
+        "temp/{}-{}",
+        id,
+        JUDGE_RUNS.fetch_add(1, AtomicOrdering::SeqCst)
+    );
     fs::create_dir_all(&temp_dir)?;
-    let mut source_code = fs::File::create(format!("{}/{}", temp_dir, language.file_name))?;
+    let file_name = language.source_file_name(&submission.source_code);
+    let mut source_code = fs::File::create(format!("{}/{}", temp_dir, file_name))?;
     source_code.write_all(submission.source_code.as_bytes())?;
 
     //Compilation arguments preparation
     let args = &language
         .command
         .iter()
-        .map(|arg| match arg.as_str() {
-            "%INPUT%" => format!("{}/{}", temp_dir, language.file_name),
-            "%OUTPUT%" => format!("{}/{}", temp_dir, "target"),
-            other => other.to_string(),
+        .map(|arg| {
+            arg.replace("%INPUT%", &format!("{}/{}", temp_dir, file_name))
+                .replace("%OUTPUT%", &format!("{}/{}", temp_dir, "target"))
+                .replace("%DIR%", &temp_dir)
+                .replace("%CLASS%", &main_class_name(&submission.source_code))
         })
         .collect::<Vec<_>>()[1..];
 
@@ -670,6 +1499,7 @@ fn judge(
     let mut compile_time;
     'compile_time_measure: loop {
         compile_time = compile_instant.elapsed();
+        check_killed(id, &mut compile_child, &temp_dir)?;
         match compile_child.try_wait()? {
             Some(_) => {
                 break 'compile_time_measure;
@@ -690,14 +1520,58 @@ fn judge(
             time: compile_time.as_micros(),
             memory: 0,
             info: String::from_utf8(output.stderr)?,
+            cpu_time: None,
+        });
+        for j in 1..=cases.len() {
+            case_results.push(CaseResult {
+                id: j,
+                result: OjResult::Waiting,
+                time: 0,
+                memory: 0,
+                info: "".to_string(),
+                cpu_time: None,
+            });
+        }
+    } else if !std::path::Path::new(&format!("{}/{}", temp_dir, "target")).exists() {
+        //A misconfigured compile command may succeed without writing the binary, which is not the fault of the submission
+        result = OjResult::SystemError;
+        case_results.push(CaseResult {
+            id: 0,
+            result: OjResult::SystemError,
+            time: compile_time.as_micros(),
+            memory: 0,
+            info: "Compiler reported success but produced no output binary".to_string(),
+            cpu_time: None,
         });
-        for j in 1..=problem.cases.len() {
+        for j in 1..=cases.len() {
             case_results.push(CaseResult {
                 id: j,
                 result: OjResult::Waiting,
                 time: 0,
                 memory: 0,
                 info: "".to_string(),
+                cpu_time: None,
+            });
+        }
+    } else if compile_only {
+        //Records the result of compilation without running any case
+        result = OjResult::CompilationSuccess;
+        case_results.push(CaseResult {
+            id: 0,
+            result: OjResult::CompilationSuccess,
+            time: compile_time.as_micros(),
+            memory: 0,
+            info: "".to_string(),
+            cpu_time: None,
+        });
+        for j in 1..=cases.len() {
+            case_results.push(CaseResult {
+                id: j,
+                result: OjResult::Skipped,
+                time: 0,
+                memory: 0,
+                info: "Compile only".to_string(),
+                cpu_time: None,
             });
         }
     } else {
@@ -708,25 +1582,127 @@ fn judge(
             time: compile_time.as_micros(),
             memory: 0,
             info: "".to_string(),
+            cpu_time: None,
         });
 
         //Runs each case
-        'cases: for (i, case) in problem.cases.iter().enumerate() {
-            //Prepares the input, output and the answer
-            let infile = fs::File::open(&case.input_file)?;
+        'cases: for (i, case) in cases.iter().enumerate() {
+            //Skips the remaining cases once the total budget is used up
+            if budget_exceeded() {
+                result = match result {
+                    OjResult::Accepted => OjResult::TimeLimitExceeded,
+                    result => result,
+                };
+                case_results.push(CaseResult {
+                    id: i + 1,
+                    result: OjResult::Skipped,
+                    time: 0,
+                    memory: 0,
+                    info: "Total judge time exceeded".to_string(),
+                    cpu_time: None,
+                });
+                continue 'cases;
+            }
+
+            //Skips the remaining cases after the first failure if required
+            if problem.misc.fail_fast && result != OjResult::Accepted {
+                case_results.push(CaseResult {
+                    id: i + 1,
+                    result: OjResult::Skipped,
+                    time: 0,
+                    memory: 0,
+                    info: "".to_string(),
+                    cpu_time: None,
+                });
+                continue 'cases;
+            }
+
+            //Generates the input if required, a failed generator is not the fault of the submission
+            //The generator has the time limit of the case, and no more than what is left of the total budget
+            let generator_time_limit = [
+                (case.time_limit != 0).then(|| Duration::from_micros(case.time_limit)),
+                (config.server.max_total_judge_time != 0)
+                    .then(|| total_budget.saturating_sub(judge_instant.elapsed())),
+            ]
+            .into_iter()
+            .flatten()
+            .min();
+            let infile = match case.input_generator {
+                Some(ref generator) => {
+                    match generate_input(generator, id, i + 1, &temp_dir, generator_time_limit) {
+                        Ok(infile) => infile,
+                        Err(e) if e.is::<JobKilled>() => return Err(e),
+                        Err(e) => {
+                            result = match result {
+                                OjResult::Accepted => OjResult::SystemError,
+                                result => result,
+                            };
+                            case_results.push(CaseResult {
+                                id: i + 1,
+                                result: OjResult::SystemError,
+                                time: 0,
+                                memory: 0,
+                                info: format!("Input generator failed: {}", e),
+                                cpu_time: None,
+                            });
+                            continue 'cases;
+                        }
+                    }
+                }
+                None => fs::File::open(&case.input_file)?,
+            };
+
+            //Prepares the output and the answer
             let outfile = fs::File::create(format!("{}/{}", temp_dir, "output"))?;
-            let answer = fs::read_to_string(&case.answer_file)?;
+            //Binary answers are kept as raw bytes, since they may not be valid UTF-8
+            let answer_bytes = std::iter::once(&case.answer_file)
+                .chain(&case.answer_files)
+                .map(fs::read)
+                .collect::<Result<Vec<_>, _>>()?;
+            let answers = match problem.misc.binary_output {
+                true => Vec::new(),
+                false => answer_bytes
+                    .iter()
+                    .map(|answer| String::from_utf8(answer.clone()))
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
 
             //Runs the case in a child process and records the time it took
             let run_instant = Instant::now();
             let mut run_time;
-            let mut run_child = Command::new(format!("{}/{}", temp_dir, "target"))
+            let mut run_command = Command::new(format!("{}/{}", temp_dir, "target"));
+            run_command
                 .stdin(Stdio::from(infile))
                 .stdout(Stdio::from(outfile))
-                .stderr(Stdio::piped())
-                .spawn()?;
-            'run_time_measure: loop {
+                .stderr(Stdio::piped());
+            limit_cpu_time(&mut run_command, case.cpu_time_limit);
+            limit_processes(
+                &mut run_command,
+                problem
+                    .misc
+                    .max_processes
+                    .unwrap_or(config.server.max_processes),
+            );
+            let mut run_child = run_command.spawn()?;
+            let (status, cpu_time) = 'run_time_measure: loop {
                 run_time = run_instant.elapsed();
+                check_killed(id, &mut run_child, &temp_dir)?;
+                if budget_exceeded() {
+                    run_child.kill()?;
+                    result = match result {
+                        OjResult::Accepted => OjResult::TimeLimitExceeded,
+                        result => result,
+                    };
+                    case_results.push(CaseResult {
+                        id: i + 1,
+                        result: OjResult::TimeLimitExceeded,
+                        time: run_time.as_micros(),
+                        memory: 0,
+                        info: "Total judge time exceeded".to_string(),
+                        cpu_time: None,
+                    });
+                    continue 'cases;
+                }
                 if case.time_limit != 0 && run_time > Duration::from_micros(case.time_limit) {
                     run_child.kill()?;
                     result = match result {
@@ -739,24 +1715,49 @@ fn judge(
                         time: case.time_limit as u128,
                         memory: 0,
                         info: format!("Time limit: {}", case.time_limit),
+                        cpu_time: None,
                     });
                     continue 'cases;
                 }
-                match run_child.try_wait()? {
-                    Some(_) => {
-                        break 'run_time_measure;
+                match try_wait(&mut run_child)? {
+                    Some(exited) => {
+                        break 'run_time_measure exited;
                     }
                     None => {}
                 }
-            }
+            };
 
             //Collects the result
-            let output = run_child.wait_with_output()?;
-            let stdout = fs::read_to_string(format!("{}/{}", temp_dir, "output"))?;
-            let stderr = String::from_utf8(output.stderr)?;
+            let output = fs::read(format!("{}/{}", temp_dir, "output"))?;
+            let stdout = match problem.misc.binary_output {
+                true => String::new(),
+                false => String::from_utf8(output.clone())?,
+            };
+            let mut stderr = String::new();
+            if let Some(mut pipe) = run_child.stderr.take() {
+                io::Read::read_to_string(&mut pipe, &mut stderr)?;
+            }
+
+            //Checks whether the CPU time limit was hit, independent of the wall-clock limit
+            if cpu_time_exceeded(&status, cpu_time, case.cpu_time_limit) {
+                result = match result {
+                    OjResult::Accepted => OjResult::TimeLimitExceeded,
+                    result => result,
+                };
+                case_results.push(CaseResult {
+                    id: i + 1,
+                    result: OjResult::TimeLimitExceeded,
+                    time: run_time.as_micros(),
+                    memory: 0,
+                    info: format!("CPU time limit: {}", case.cpu_time_limit),
+                    cpu_time: cpu_time.map(|t| t.as_micros()),
+                });
+                continue 'cases;
+            }
+            let cpu_time = cpu_time.map(|t| t.as_micros());
 
             //Checks whether runtime error occurred
-            if !output.status.success() {
+            if !status.success() {
                 result = match result {
                     OjResult::Accepted => OjResult::RuntimeError,
                     result => result,
@@ -767,17 +1768,59 @@ fn judge(
                     time: run_time.as_micros(),
                     memory: 0,
                     info: stderr,
+                    cpu_time,
                 });
+            } else if problem.misc.binary_output
+                && !matches!(problem.problem_type, ProblemType::Spj)
+            {
+                //Neither splits nor trims, and only notes the size instead of the raw output
+                if answer_bytes.contains(&output) {
+                    score += case.score;
+                    case_results.push(CaseResult {
+                        id: i + 1,
+                        result: OjResult::Accepted,
+                        time: run_time.as_micros(),
+                        memory: 0,
+                        info: format!("Binary output of {} bytes", output.len()),
+                        cpu_time,
+                    });
+                } else {
+                    result = match result {
+                        OjResult::Accepted => OjResult::WrongAnswer,
+                        result => result,
+                    };
+                    let position = output
+                        .iter()
+                        .zip(&answer_bytes[0])
+                        .position(|(l, r)| l != r)
+                        .unwrap_or_else(|| output.len().min(answer_bytes[0].len()));
+                    case_results.push(CaseResult {
+                        id: i + 1,
+                        result: OjResult::WrongAnswer,
+                        time: run_time.as_micros(),
+                        memory: 0,
+                        info: format!(
+                            "Binary output of {} bytes, differs from the answer at byte {}",
+                            output.len(),
+                            position
+                        ),
+                        cpu_time,
+                    });
+                }
             } else {
                 //Judges the result according to the problem type
                 match problem.problem_type {
                     ProblemType::Standard | ProblemType::DynamicRanking => {
-                        if stdout
-                            .split('\n')
-                            .map(|l| l.trim())
-                            .zip(answer.split('\n').map(|l| l.trim()))
-                            .fold(true, |acc, (l, r)| if acc && l == r { true } else { false })
-                        {
+                        //Lines are trimmed of any Unicode whitespace, including a trailing CR
+                        let compared = normalize_unicode(&stdout, &problem.misc);
+                        if answers.iter().any(|answer| {
+                            let answer = normalize_unicode(answer, &problem.misc);
+                            compared
+                                .split('\n')
+                                .map(|l| l.trim())
+                                .zip(answer.split('\n').map(|l| l.trim()))
+                                .fold(true, |acc, (l, r)| if acc && l == r { true } else { false })
+                        }) {
                             score += case.score;
                             case_results.push(CaseResult {
                                 id: i + 1,
@@ -785,6 +1828,7 @@ fn judge(
                                 time: run_time.as_micros(),
                                 memory: 0,
                                 info: stdout,
+                                cpu_time,
                             });
                         } else {
                             result = match result {
@@ -796,12 +1840,23 @@ fn judge(
                                 result: OjResult::WrongAnswer,
                                 time: run_time.as_micros(),
                                 memory: 0,
-                                info: stdout,
+                                info: wrong_answer_info(stdout, &answers, &problem.misc),
+                                cpu_time,
                             });
                         }
                     }
                     ProblemType::Strict => {
-                        if stdout == answer {
+                        //Only CRLF is turned into LF, any other whitespace still counts
+                        let compared = normalize_unicode(&stdout, &problem.misc);
+                        let matched = answers.iter().any(|answer| {
+                            let answer = normalize_unicode(answer, &problem.misc);
+                            if problem.misc.normalize_newlines {
+                                compared.replace("\r\n", "\n") == answer.replace("\r\n", "\n")
+                            } else {
+                                compared == answer
+                            }
+                        });
+                        if matched {
                             score += case.score;
                             case_results.push(CaseResult {
                                 id: i + 1,
@@ -809,6 +1864,7 @@ fn judge(
                                 time: run_time.as_micros(),
                                 memory: 0,
                                 info: stdout,
+                                cpu_time,
                             });
                         } else {
                             result = match result {
@@ -820,7 +1876,8 @@ fn judge(
                                 result: OjResult::WrongAnswer,
                                 time: run_time.as_micros(),
                                 memory: 0,
-                                info: stdout,
+                                info: wrong_answer_info(stdout, &answers, &problem.misc),
+                                cpu_time,
                             });
                         }
                     }
@@ -847,6 +1904,7 @@ fn judge(
                                     memory: 0,
                                     info: "Error occurred while calling the special judger"
                                         .to_string(),
+                                    cpu_time,
                                 })
                             } else {
                                 let stdout = String::from_utf8(output.stdout)?
@@ -865,6 +1923,7 @@ fn judge(
                                         time: run_time.as_micros(),
                                         memory: 0,
                                         info: "Invalid special judge output.".to_string(),
+                                        cpu_time,
                                     })
                                 } else {
                                     match serde_json::from_str(&format!("\"{}\"", &stdout[0])) {
@@ -877,6 +1936,7 @@ fn judge(
                                                     time: run_time.as_micros(),
                                                     memory: 0,
                                                     info: stdout[1].clone(),
+                                                    cpu_time,
                                                 })
                                             }
                                             other => {
@@ -890,6 +1950,7 @@ fn judge(
                                                     time: run_time.as_micros(),
                                                     memory: 0,
                                                     info: stdout[1].clone(),
+                                                    cpu_time,
                                                 })
                                             }
                                         },
@@ -899,6 +1960,7 @@ fn judge(
                                             time: run_time.as_micros(),
                                             memory: 0,
                                             info: "Invalid special judge output.".to_string(),
+                                            cpu_time,
                                         }),
                                     }
                                 }
@@ -910,6 +1972,7 @@ fn judge(
                             time: run_time.as_micros(),
                             memory: 0,
                             info: "Special judge command not found".to_string(),
+                            cpu_time,
                         }),
                     },
                 }
@@ -920,6 +1983,11 @@ fn judge(
     //Cleans up
     fs::remove_dir_all(&temp_dir)?;
 
+    //Without groups the score is the sum kept while judging the cases
+    if !problem.misc.groups.is_empty() {
+        score = problem.score(&case_results);
+    }
+
     Ok(Job {
         id,
         created_time,
@@ -929,14 +1997,17 @@ fn judge(
         result,
         score,
         cases: case_results,
+        language_command: Some(language.command.clone()),
+        config_version: problem.config_version(),
     })
 }
 
 ///POST requests for "/users" handler
 #[post("/users")]
 async fn post_users(
+    req: HttpRequest,
     user: web::Json<User>,
-    _config: web::Data<Config>,
+    config: web::Data<Config>,
     pool: web::Data<Pool<SqliteConnectionManager>>,
 ) -> impl Responder {
     //Unwraps the arguments
@@ -947,36 +2018,28 @@ async fn post_users(
 
     //Checks if the user name already exists
     match oj_try!(User::select_by_name(&user.name, &pool)) {
-        Some(_) => HttpResponse::BadRequest().body(
-            serde_json::to_string(&ErrorResponseBody {
-                code: 1,
-                reason: ErrorReason::ErrInvalidArgument,
-                message: format!("User name '{}' already exists.", user.name),
-            })
-            .unwrap(),
-        ),
+        Some(_) => invalid_argument(Message::UserNameExists(user.name.clone())).response(&req),
         None => match user.id {
             //If id is provided then does update
             Some(id) => match oj_try!(User::select_by_id(id, &pool)) {
-                Some(_) => {
+                Some(original_user) => {
+                    if let Some(response) = check_auth(&req, &original_user, &config) {
+                        return response;
+                    }
                     oj_try!(user.update(&pool));
                     HttpResponse::Ok().body(serde_json::to_string(&user).unwrap())
                 }
-                None => HttpResponse::NotFound().body(
-                    serde_json::to_string(&ErrorResponseBody {
-                        code: 3,
-                        reason: ErrorReason::ErrNotFound,
-                        message: format!("User {} not found.", id),
-                    })
-                    .unwrap(),
-                ),
+                None => not_found(Resource::User, id).response(&req),
             },
             //Otherwise does insert
             None => {
-                oj_try!(user.insert(&pool));
+                let id = oj_try!(user.insert(&pool));
                 HttpResponse::Ok().body(
-                    serde_json::to_string(&oj_try!(User::select_by_name(&user.name, &pool)))
-                        .unwrap(),
+                    serde_json::to_string(&User {
+                        id: Some(id),
+                        ..user
+                    })
+                    .unwrap(),
                 )
             }
         },
@@ -986,6 +2049,7 @@ async fn post_users(
 ///POST requests for "/contests" handler
 #[post("/contests")]
 async fn post_contests(
+    req: HttpRequest,
     contest: web::Json<Contest>,
     config: web::Data<Config>,
     pool: web::Data<Pool<SqliteConnectionManager>>,
@@ -1031,20 +2095,32 @@ async fn post_contests(
             }
         }))
     } {
-        return HttpResponse::NotFound().body(
-            serde_json::to_string(&ErrorResponseBody {
-                code: 3,
-                reason: ErrorReason::ErrNotFound,
-                message: format!(
-                    "Contest {} not found.",
-                    match contest.id {
-                        Some(id) => id,
-                        None => oj_try!(Contest::count(&pool)) + 1,
-                    }
-                ),
-            })
-            .unwrap(),
-        );
+        return not_found(
+            Resource::Contest,
+            match contest.id {
+                Some(id) => id,
+                None => oj_try!(Contest::count(&pool)) + 1,
+            },
+        )
+        .response(&req);
+    }
+
+    //Limits can only be overridden for the problems in the contest
+    for limit_override in &contest.limit_overrides {
+        if !contest.problem_ids.contains(&limit_override.problem_id) {
+            return invalid_argument(Message::OverrideWithoutProblem(limit_override.problem_id))
+                .response(&req);
+        }
+    }
+
+    if let Some(ref limits) = contest.per_problem_submission_limit {
+        if limits.len() != contest.problem_ids.len() {
+            return invalid_argument(Message::SubmissionLimitCount(
+                limits.len(),
+                contest.problem_ids.len(),
+            ))
+            .response(&req);
+        }
     }
 
     match contest.id {
@@ -1054,14 +2130,7 @@ async fn post_contests(
                 oj_try!(contest.update(&pool));
                 HttpResponse::Ok().body(serde_json::to_string(&contest).unwrap())
             }
-            None => HttpResponse::NotFound().body(
-                serde_json::to_string(&ErrorResponseBody {
-                    code: 3,
-                    reason: ErrorReason::ErrNotFound,
-                    message: format!("Contest {} not found.", id),
-                })
-                .unwrap(),
-            ),
+            None => not_found(Resource::Contest, id).response(&req),
         },
         //Otherwise does insert
         None => {
@@ -1077,7 +2146,9 @@ async fn post_contests(
 ///GET requests for "/jobs" handler
 #[get("/jobs")]
 async fn get_jobs(
+    req: HttpRequest,
     query: web::Query<Filter>,
+    tz: web::Query<TimeZoneQuery>,
     _config: web::Data<Config>,
     pool: web::Data<Pool<SqliteConnectionManager>>,
 ) -> impl Responder {
@@ -1086,23 +2157,149 @@ async fn get_jobs(
     //Unwraps the arguments
     let query = query.into_inner();
     let pool = pool.into_inner();
+    let tz = match tz.time_zone() {
+        Ok(tz) => tz,
+        Err(message) => return invalid_argument(message).response(&req),
+    };
 
     //Filters the jobs
-    HttpResponse::Ok().body(serde_json::to_string(&oj_try!(query.apply(&pool))).unwrap())
+    HttpResponse::Ok().body(jobs_in_time_zone(&oj_try!(query.apply(&pool)), tz))
 }
 
 ///GET requests for "/users" handler
 #[get("/users")]
 async fn get_users(
+    query: web::Query<UserFilter>,
     _config: web::Data<Config>,
     pool: web::Data<Pool<SqliteConnectionManager>>,
 ) -> impl Responder {
     //Unwraps the arguments
+    let query = query.into_inner();
     let pool = pool.into_inner();
 
     log::info!(target: "get_users_handler", "Handling GET for users");
 
-    HttpResponse::Ok().body(serde_json::to_string(&oj_try!(User::select_all(&pool))).unwrap())
+    HttpResponse::Ok().body(
+        serde_json::to_string(&oj_try!(User::select_filtered(
+            query.name.as_deref(),
+            query.limit,
+            query.offset,
+            &pool
+        )))
+        .unwrap(),
+    )
+}
+
+///GET requests for "/users/{userId}/solved" handler
+#[get("/users/{userId}/solved")]
+async fn get_users_solved(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    query: web::Query<SolvedFilter>,
+    _config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+    let query = query.into_inner();
+    let pool = pool.into_inner();
+
+    log::info!(target: "get_users_solved_handler", "Handling GET for problems solved by user {}", id);
+
+    if oj_try!(User::select_by_id(id, &pool)).is_none() {
+        return not_found(Resource::User, id).response(&req);
+    }
+
+    //Keeps the earliest accepted job of every problem
+    let jobs = oj_try!(Filter {
+        user_id: Some(id),
+        contest_id: query.contest_id,
+        result: Some(OjResult::Accepted),
+        ..Default::default()
+    }
+    .apply(&pool));
+    let mut solved: BTreeMap<usize, SolvedProblem> = BTreeMap::new();
+    for job in jobs {
+        let problem_id = job.submission.problem_id;
+        match solved.get(&problem_id) {
+            Some(earliest) if *earliest.time <= *job.created_time => {}
+            _ => {
+                solved.insert(
+                    problem_id,
+                    SolvedProblem {
+                        problem_id,
+                        job_id: job.id,
+                        time: job.created_time,
+                    },
+                );
+            }
+        }
+    }
+
+    HttpResponse::Ok()
+        .body(serde_json::to_string(&solved.into_values().collect::<Vec<_>>()).unwrap())
+}
+
+///GET requests for "/languages" handler
+#[get("/languages")]
+async fn get_languages(config: web::Data<Config>) -> impl Responder {
+    log::info!(target: "get_languages_handler", "Handling GET for languages");
+
+    HttpResponse::Ok().body(serde_json::to_string(&config.languages).unwrap())
+}
+
+///GET requests for "/problems/{problemId}" handler
+#[get("/problems/{problemId}")]
+async fn get_problems_by_id(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    config: web::Data<Config>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+
+    log::info!(target: "get_problems_by_id_handler", "Handling GET for problem {}", id);
+
+    match config.problems.iter().find(|problem| problem.id == id) {
+        Some(problem) => HttpResponse::Ok().body(
+            serde_json::to_string(&ProblemStatement {
+                id: problem.id,
+                name: &problem.name,
+                problem_type: problem.problem_type,
+                sample_cases: &problem.sample_cases,
+            })
+            .unwrap(),
+        ),
+        None => not_found(Resource::Problem, id).response(&req),
+    }
+}
+
+///GET requests for "/problems/{problemId}/samples" handler
+#[get("/problems/{problemId}/samples")]
+async fn get_problems_samples(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    config: web::Data<Config>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+
+    log::info!(target: "get_problems_samples_handler", "Handling GET for problem {}", id);
+
+    let problem = match config.problems.iter().find(|problem| problem.id == id) {
+        Some(problem) => problem,
+        None => {
+            return not_found(Resource::Problem, id).response(&req);
+        }
+    };
+
+    //Reads the contents of the sample files, the other judged cases stay hidden
+    let mut samples = vec![];
+    for case in problem.public_cases() {
+        samples.push(oj_try!(Sample::read(case)));
+    }
+
+    HttpResponse::Ok().body(serde_json::to_string(&samples).unwrap())
 }
 
 ///GET requests for "/contests" handler
@@ -1119,39 +2316,112 @@ async fn get_contests(
     HttpResponse::Ok().body(serde_json::to_string(&oj_try!(Contest::select_all(&pool))).unwrap())
 }
 
-///GET requests for "/jobs/{jobId}" handler
-#[get("/jobs/{jobId}")]
-async fn get_jobs_by_id(
+///GET requests for "/jobs/{jobId}" handler
+#[get("/jobs/{jobId}")]
+async fn get_jobs_by_id(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    tz: web::Query<TimeZoneQuery>,
+    _config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+    let pool = pool.into_inner();
+    let tz = match tz.time_zone() {
+        Ok(tz) => tz,
+        Err(message) => return invalid_argument(message).response(&req),
+    };
+
+    log::info!(target: "get_jobs_by_id_handler", "Handling GET for job {}", id);
+
+    //Selects the chosen job
+    let job = oj_try!(Job::select_by_id(id, &pool));
+    match job {
+        Some(job) => HttpResponse::Ok().body(jobs_in_time_zone(&job, tz)),
+        None => {
+            return not_found(Resource::Job, id).response(&req);
+        }
+    }
+}
+
+///GET requests for "/jobs/{jobId}/cases" handler
+#[get("/jobs/{jobId}/cases")]
+async fn get_jobs_cases(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    query: web::Query<CaseFilter>,
+    _config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+    let query = query.into_inner();
+    let pool = pool.into_inner();
+
+    log::info!(target: "get_jobs_cases_handler", "Handling GET for cases of job {}", id);
+
+    //Selects the cases of the chosen job
+    match oj_try!(Job::select_by_id(id, &pool)) {
+        Some(job) => HttpResponse::Ok().body(
+            serde_json::to_string(
+                &job.cases
+                    .into_iter()
+                    .filter(|case| match query.result {
+                        Some(result) => case.result == result,
+                        None => true,
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap(),
+        ),
+        None => not_found(Resource::Job, id).response(&req),
+    }
+}
+
+///GET requests for "/jobs/{jobId}/source" handler
+///Responds with the raw source code instead of the JSON-escaped one in the job
+#[get("/jobs/{jobId}/source")]
+async fn get_jobs_source(
+    req: HttpRequest,
     path: web::Path<usize>,
-    _config: web::Data<Config>,
+    config: web::Data<Config>,
     pool: web::Data<Pool<SqliteConnectionManager>>,
 ) -> impl Responder {
     //Unwraps the arguments
     let id = path.into_inner();
     let pool = pool.into_inner();
 
-    log::info!(target: "get_jobs_by_id_handler", "Handling GET for job {}", id);
+    log::info!(target: "get_jobs_source_handler", "Handling GET for source of job {}", id);
 
-    //Selects the chosen job
-    let job = oj_try!(Job::select_by_id(id, &pool));
-    match job {
-        Some(job) => HttpResponse::Ok().body(serde_json::to_string(&job).unwrap()),
-        None => {
-            return HttpResponse::NotFound().body(
-                serde_json::to_string(&ErrorResponseBody {
-                    code: 3,
-                    reason: ErrorReason::ErrNotFound,
-                    message: format!("Job {} not found.", id),
-                })
-                .unwrap(),
-            );
-        }
+    let submission = match oj_try!(Job::select_by_id(id, &pool)) {
+        Some(job) => job.submission,
+        None => return not_found(Resource::Job, id).response(&req),
+    };
+
+    //Names the file as it is judged, unless the language is no longer configured
+    let mut response = HttpResponse::Ok();
+    response.content_type("text/plain; charset=utf-8");
+    if let Some(language) = config
+        .languages
+        .iter()
+        .find(|lang| lang.full_name() == submission.language)
+    {
+        response.insert_header((
+            "Content-Disposition",
+            format!(
+                "inline; filename=\"{}\"",
+                language.source_file_name(&submission.source_code)
+            ),
+        ));
     }
+    response.body(submission.source_code)
 }
 
 ///GET requests for "/contests/{contestId}" handler
 #[get("/contests/{contestId}")]
 async fn get_contests_by_id(
+    req: HttpRequest,
     path: web::Path<usize>,
     _config: web::Data<Config>,
     pool: web::Data<Pool<SqliteConnectionManager>>,
@@ -1167,23 +2437,123 @@ async fn get_contests_by_id(
     match contest {
         Some(contest) => HttpResponse::Ok().body(serde_json::to_string(&contest).unwrap()),
         None => {
-            return HttpResponse::NotFound().body(
-                serde_json::to_string(&ErrorResponseBody {
-                    code: 3,
-                    reason: ErrorReason::ErrNotFound,
-                    message: format!("Contest {} not found.", id),
-                })
-                .unwrap(),
-            );
+            return not_found(Resource::Contest, id).response(&req);
+        }
+    }
+}
+
+///User ids and problem ids of a contest
+type ContestMembers = (Vec<usize>, Vec<usize>);
+
+///Gets the user ids and problem ids of a contest
+///Returns None if the contest does not exist
+fn contest_members(
+    id: usize,
+    config: &Config,
+    pool: &Pool<SqliteConnectionManager>,
+) -> Result<Option<ContestMembers>, Box<dyn Error>> {
+    match Contest::select_by_id(id, pool)? {
+        //If id provided is not 0 and the contest with the id exists then uses the specified contest
+        Some(contest) => Ok(Some((contest.user_ids, contest.problem_ids))),
+        None => {
+            //If id provided is 0 then uses all the users and problems
+            if id == 0 {
+                Ok(Some((
+                    (0..User::count(pool)?).collect(),
+                    config.problems.iter().map(|p| p.id).collect(),
+                )))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+}
+
+///Selected submission of a user on a problem in a contest
+#[derive(Serialize, Clone, Debug)]
+pub struct ContestSubmission {
+    user_id: usize,
+    problem_id: usize,
+    job_id: Option<usize>,
+    score: f32,
+    submission_count: usize,
+}
+
+///GET requests for "/contests/{contestId}/submissions" handler
+#[get("/contests/{contestId}/submissions")]
+async fn get_contests_submissions(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    rule: web::Query<RankingRule>,
+    config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+    let rule = rule.into_inner();
+    let config = config.into_inner();
+    let pool = pool.into_inner();
+
+    log::info!(target: "get_contests_submissions_handler", "Handling GET for contest {}", id);
+
+    let (user_ids, problem_ids) = match oj_try!(contest_members(id, &config, &pool)) {
+        Some(members) => members,
+        None => {
+            return not_found(Resource::Contest, id).response(&req);
+        }
+    };
+
+    //Selects the valid job of each user on each problem the same way as the ranklist does
+    let mut submissions = vec![];
+    for user_id in &user_ids {
+        for problem_id in &problem_ids {
+            let filtered_jobs = oj_try!(Filter {
+                user_id: Some(*user_id),
+                contest_id: Some(id),
+                problem_id: Some(*problem_id),
+                ..Default::default()
+            }
+            .apply(&pool));
+
+            let problem = config
+                .problems
+                .iter()
+                .find(|problem| problem.id == *problem_id)
+                .unwrap();
+
+            //The latest submission counts once a dynamic ranking problem is accepted
+            let job = if problem.problem_type == ProblemType::DynamicRanking
+                && filtered_jobs
+                    .iter()
+                    .any(|job| job.result == OjResult::Accepted)
+            {
+                ScoringRule::Latest.select(&filtered_jobs)
+            } else {
+                rule.scoring_rule
+                    .unwrap_or(ScoringRule::Latest)
+                    .select(&filtered_jobs)
+            };
+
+            submissions.push(ContestSubmission {
+                user_id: *user_id,
+                problem_id: *problem_id,
+                job_id: job.map(|job| job.id),
+                score: job.map(|job| job.score).unwrap_or(0.0),
+                submission_count: filtered_jobs.len(),
+            });
         }
     }
+
+    HttpResponse::Ok().body(serde_json::to_string(&submissions).unwrap())
 }
 
 ///GET requests for "/contests/{contestId}/ranklist" handler
 #[get("/contests/{contestId}/ranklist")]
 async fn get_contests_ranklist(
+    req: HttpRequest,
     path: web::Path<usize>,
     rule: web::Query<RankingRule>,
+    query: web::Query<RanklistQuery>,
     config: web::Data<Config>,
     pool: web::Data<Pool<SqliteConnectionManager>>,
 ) -> impl Responder {
@@ -1195,33 +2565,22 @@ async fn get_contests_ranklist(
 
     log::info!(target: "get_contests_ranklist_handler", "Handling GET for contest {}", id);
 
+    //Serves the cached ranklist unless a refresh is requested
+    let format = query.format;
+    if !query.refresh {
+        if let Some(ranklist) = RANKLIST_CACHE.get(&(id, rule, format)) {
+            return format.response(ranklist);
+        }
+    }
+    let generation = RANKLIST_CACHE.generation();
+
     //Declares the variables to be used
-    let user_ids;
-    let problem_ids;
     let mut usersranking = vec![];
 
-    match oj_try!(Contest::select_by_id(id, &pool)) {
-        //If id provided is not 0 and the contest with the id exists then ranks the specified contest
-        Some(contest) => {
-            user_ids = contest.user_ids.clone();
-            problem_ids = contest.problem_ids.clone();
-        }
+    let (user_ids, problem_ids) = match oj_try!(contest_members(id, &config, &pool)) {
+        Some(members) => members,
         None => {
-            //If id provided is 0 then ranks globally
-            if id == 0 {
-                user_ids = (0..oj_try!(User::count(&pool))).collect();
-                problem_ids = config.problems.iter().map(|p| p.id).collect();
-            } else {
-                //Otherwise raises error
-                return HttpResponse::NotFound().body(
-                    serde_json::to_string(&ErrorResponseBody {
-                        code: 3,
-                        reason: ErrorReason::ErrNotFound,
-                        message: format!("Contest {} not found.", id),
-                    })
-                    .unwrap(),
-                );
-            }
+            return not_found(Resource::Contest, id).response(&req);
         }
     };
 
@@ -1256,17 +2615,8 @@ async fn get_contests_ranklist(
                 let dynamic_ranking_ratio = match problem.misc.dynamic_ranking_ratio {
                     Some(ratio) => ratio,
                     None => {
-                        return HttpResponse::BadRequest().body(
-                            serde_json::to_string(&ErrorResponseBody {
-                                code: 1,
-                                reason: ErrorReason::ErrInvalidArgument,
-                                message: format!(
-                                    "Dynamic ranking ratio of problem {} not found.",
-                                    problem.id
-                                ),
-                            })
-                            .unwrap(),
-                        );
+                        return invalid_argument(Message::DynamicRankingRatioMissing(problem.id))
+                            .response(&req);
                     }
                 };
 
@@ -1278,14 +2628,10 @@ async fn get_contests_ranklist(
                 //If there is not any accepted submissions then selects the valid job according to the scoring rule
                 //Otherwise selects the latest accepted submission
                 if accepted_jobs.len() == 0 {
-                    let job = match rule.scoring_rule.unwrap_or(ScoringRule::Latest) {
-                        ScoringRule::Latest => {
-                            filtered_jobs.iter().max_by_key(|job| *job.created_time)
-                        }
-                        ScoringRule::Highest => filtered_jobs
-                            .iter()
-                            .max_by(|l, r| l.score.partial_cmp(&r.score).unwrap()),
-                    };
+                    let job = rule
+                        .scoring_rule
+                        .unwrap_or(ScoringRule::Latest)
+                        .select(&filtered_jobs);
 
                     match job {
                         Some(job) => {
@@ -1334,12 +2680,10 @@ async fn get_contests_ranklist(
                 }
             } else {
                 //Selects the valid job according to the scoring rule
-                let job = match rule.scoring_rule.unwrap_or(ScoringRule::Latest) {
-                    ScoringRule::Latest => filtered_jobs.iter().max_by_key(|job| *job.created_time),
-                    ScoringRule::Highest => filtered_jobs
-                        .iter()
-                        .max_by(|l, r| l.score.partial_cmp(&r.score).unwrap()),
-                };
+                let job = rule
+                    .scoring_rule
+                    .unwrap_or(ScoringRule::Latest)
+                    .select(&filtered_jobs);
 
                 match job {
                     Some(job) => {
@@ -1374,6 +2718,16 @@ async fn get_contests_ranklist(
         });
     }
 
+    //Drops the inactive users before ranking so that the ranks stay contiguous
+    if rule.hide_inactive {
+        usersranking.retain(|ranking| ranking.submission_count > 0);
+        if usersranking.is_empty() {
+            let ranklist = format.render(&usersranking, &problem_ids);
+            RANKLIST_CACHE.put((id, rule, format), generation, ranklist.clone());
+            return format.response(ranklist);
+        }
+    }
+
     //Breaks the ties according to the given rule
     usersranking.sort_by(|l, r| {
         match l
@@ -1487,15 +2841,92 @@ async fn get_contests_ranklist(
         }
     }
 
-    HttpResponse::Ok().body(serde_json::to_string(&usersranking).unwrap())
+    let ranklist = format.render(&usersranking, &problem_ids);
+    RANKLIST_CACHE.put((id, rule, format), generation, ranklist.clone());
+    format.response(ranklist)
+}
+
+///GET requests for "/contests/{contestId}/announcements" handler
+#[get("/contests/{contestId}/announcements")]
+async fn get_contests_announcements(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    _config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+    let pool = pool.into_inner();
+
+    log::info!(target: "get_contests_announcements_handler", "Handling GET for contest {}", id);
+
+    if oj_try!(Contest::select_by_id(id, &pool)).is_none() {
+        return not_found(Resource::Contest, id).response(&req);
+    }
+
+    //The latest announcement comes first
+    let mut announcements = oj_try!(Announcement::select_all_by_contest(id, &pool));
+    announcements.sort_by(|l, r| r.created_time.cmp(&l.created_time).then(r.id.cmp(&l.id)));
+
+    HttpResponse::Ok().body(serde_json::to_string(&announcements).unwrap())
+}
+
+///POST requests for "/contests/{contestId}/announcements" handler
+#[post("/contests/{contestId}/announcements")]
+async fn post_contests_announcements(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    request: web::Json<AnnouncementRequest>,
+    config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+    let request = request.into_inner();
+    let pool = pool.into_inner();
+
+    log::info!(target: "post_contests_announcements_handler", "Handling POST for contest {}", id);
+
+    if oj_try!(Contest::select_by_id(id, &pool)).is_none() {
+        return not_found(Resource::Contest, id).response(&req);
+    }
+
+    let author = match oj_try!(User::select_by_id(request.author_id, &pool)) {
+        Some(author) => author,
+        None => {
+            return not_found(Resource::User, request.author_id).response(&req);
+        }
+    };
+    if let Some(response) = check_auth(&req, &author, &config) {
+        return response;
+    }
+
+    //Only the administrator (root) can make announcements
+    if request.author_id != 0 {
+        return forbidden(Message::CannotAnnounce(request.author_id)).response(&req);
+    }
+
+    let mut announcement = Announcement {
+        id: 0,
+        contest_id: id,
+        content: request.content,
+        created_time: UtcDateTime { time: Utc::now() },
+        author_id: request.author_id,
+    };
+    announcement.id = oj_try!(announcement.insert(&pool));
+
+    HttpResponse::Ok().body(serde_json::to_string(&announcement).unwrap())
 }
 
 ///PUT requests for "/jobs/{jobId}" handler
 #[put("/jobs/{jobId}")]
 async fn put_jobs_by_id(
+    req: HttpRequest,
     path: web::Path<usize>,
+    options: web::Query<RejudgeOptions>,
     config: web::Data<Config>,
     pool: web::Data<Pool<SqliteConnectionManager>>,
+    queue: web::Data<JudgeQueue>,
 ) -> impl Responder {
     //Unwraps the arguments
     let id = path.into_inner();
@@ -1508,33 +2939,246 @@ async fn put_jobs_by_id(
     let original_job = match oj_try!(Job::select_by_id(id, &pool)) {
         Some(job) => job,
         None => {
-            return HttpResponse::NotFound().body(
-                serde_json::to_string(&ErrorResponseBody {
-                    code: 3,
-                    reason: ErrorReason::ErrNotFound,
-                    message: format!("Job {} not found.", id),
-                })
-                .unwrap(),
-            );
+            return not_found(Resource::Job, id).response(&req);
+        }
+    };
+
+    //Only finished jobs can be rejudged
+    if original_job.state != OjState::Finished {
+        return invalid_state(Message::JobNotFinished(id)).response(&req);
+    }
+
+    //The problem may have been changed or removed by the current configuration
+    let problem = match config
+        .problems
+        .iter()
+        .find(|problem| problem.id == original_job.submission.problem_id)
+    {
+        Some(problem) => problem,
+        None => {
+            return not_found(Resource::Problem, original_job.submission.problem_id).response(&req);
         }
     };
+    if problem.config_version() != original_job.config_version && !options.force {
+        return invalid_state(Message::ProblemChanged(problem.id, id)).response(&req);
+    }
 
-    //Does rejudging
+    //Puts the job back into the queue for rejudging
     let updated_time = UtcDateTime { time: Utc::now() };
-    let job = oj_try!(judge(
+    let contest = oj_try!(Contest::select_by_id(
+        original_job.submission.contest_id,
+        &pool
+    ));
+    let job = Job::queueing(
         id,
         &original_job.submission,
-        config.clone(),
+        problem.cases.len(),
+        problem.config_version(),
         original_job.created_time,
         updated_time,
-    ));
+    );
 
     //Stores to the SQLite database
     oj_try!(job.update(&pool));
+    queue
+        .into_inner()
+        .push(job.clone(), config.clone(), contest, pool.clone(), false);
+
+    HttpResponse::Ok().body(serde_json::to_string(&job).unwrap())
+}
+
+///POST requests for "/jobs/{jobId}/kill" handler
+///Stops judging a running job, e.g. one stuck before the total judge time runs out, and marks it canceled
+#[post("/jobs/{jobId}/kill")]
+async fn post_jobs_kill(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+    let pool = pool.into_inner();
+
+    log::info!(target: "post_jobs_kill_handler", "Handling POST for killing job {}", id);
+
+    //Only the administrator (root) can kill jobs
+    let admin = match oj_try!(User::select_by_id(0, &pool)) {
+        Some(admin) => admin,
+        None => {
+            return not_found(Resource::User, 0).response(&req);
+        }
+    };
+    if let Some(response) = check_auth(&req, &admin, &config) {
+        return response;
+    }
+
+    let mut job = match oj_try!(Job::select_by_id(id, &pool)) {
+        Some(job) => job,
+        None => {
+            return not_found(Resource::Job, id).response(&req);
+        }
+    };
+
+    //Whether the job is still being judged is decided by the worker, not by the stored state,
+    //which may lag behind a worker that has just finished
+    //The worker kills the child processes and removes the temporary directory when it sees the request
+    if !KILL_REQUESTS.request(id) {
+        return invalid_state(Message::JobNotRunning(id)).response(&req);
+    }
+    job.state = OjState::Canceled;
+    job.result = OjResult::Skipped;
+    job.updated_time = UtcDateTime { time: Utc::now() };
+    oj_try!(job.update(&pool));
 
     HttpResponse::Ok().body(serde_json::to_string(&job).unwrap())
 }
 
+///POST requests for "/jobs/{jobId}/verify" handler
+///Judges the submission of a finished job again and reports the differences, the stored job is left untouched
+#[post("/jobs/{jobId}/verify")]
+async fn post_jobs_verify(
+    req: HttpRequest,
+    path: web::Path<usize>,
+    options: web::Query<RejudgeOptions>,
+    config: web::Data<Config>,
+    pool: web::Data<Pool<SqliteConnectionManager>>,
+    queue: web::Data<JudgeQueue>,
+) -> impl Responder {
+    //Unwraps the arguments
+    let id = path.into_inner();
+    let config = config.into_inner();
+    let pool = pool.into_inner();
+
+    log::info!(target: "post_jobs_verify_handler", "Handling POST for job {}", id);
+
+    //Only the administrator (root) can verify jobs, as it takes a worker like a new job
+    let admin = match oj_try!(User::select_by_id(0, &pool)) {
+        Some(admin) => admin,
+        None => {
+            return not_found(Resource::User, 0).response(&req);
+        }
+    };
+    if let Some(response) = check_auth(&req, &admin, &config) {
+        return response;
+    }
+
+    //Gets the original job
+    let original_job = match oj_try!(Job::select_by_id(id, &pool)) {
+        Some(job) => job,
+        None => {
+            return not_found(Resource::Job, id).response(&req);
+        }
+    };
+
+    //Only finished jobs have a verdict to compare with
+    if original_job.state != OjState::Finished {
+        return invalid_state(Message::JobNotFinished(id)).response(&req);
+    }
+
+    //Comparing against a changed problem is meaningless unless forced
+    let problem = match config
+        .problems
+        .iter()
+        .find(|problem| problem.id == original_job.submission.problem_id)
+    {
+        Some(problem) => problem,
+        None => {
+            return not_found(Resource::Problem, original_job.submission.problem_id).response(&req);
+        }
+    };
+    if problem.config_version() != original_job.config_version && !options.force {
+        return invalid_state(Message::ProblemChanged(problem.id, id)).response(&req);
+    }
+
+    //Judges on one of the workers of the queue, the result is not stored
+    let contest = oj_try!(Contest::select_by_id(
+        original_job.submission.contest_id,
+        &pool
+    ));
+    let submission = original_job.submission.clone();
+    let (created_time, updated_time) =
+        (original_job.created_time, UtcDateTime { time: Utc::now() });
+    let verified_job = match queue
+        .run(id, move || {
+            judge(
+                id,
+                &submission,
+                config,
+                contest.as_ref(),
+                created_time,
+                updated_time,
+                false,
+            )
+            .map_err(|e| e.to_string())
+        })
+        .await
+    {
+        Ok(Ok(job)) => job,
+        Ok(Err(e)) => return internal_error(e.into()),
+        Err(e) => return internal_error(Box::new(e)),
+    };
+
+    HttpResponse::Ok()
+        .body(serde_json::to_string(&VerifyReport::compare(&original_job, &verified_job)).unwrap())
+}
+
+///GET requests for "/judges/queue" handler
+#[get("/judges/queue")]
+async fn get_judge_queue(queue: web::Data<JudgeQueue>) -> impl Responder {
+    log::info!(target: "get_judge_queue_handler", "Handling GET for judge queue");
+
+    HttpResponse::Ok().body(serde_json::to_string(&queue.status()).unwrap())
+}
+
+///Version of the API served under "/api/{API_VERSION}", changed on breaking changes
+const API_VERSION: &str = "v1";
+
+///GET requests for "/version" handler
+#[get("/version")]
+async fn get_version() -> impl Responder {
+    log::info!(target: "get_version_handler", "Handling GET for version");
+
+    HttpResponse::Ok().body(
+        serde_json::to_string(&serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "api_version": API_VERSION,
+        }))
+        .unwrap(),
+    )
+}
+
+///Registers the API handlers, mounted both under "/api/v1" and at the bare paths
+///The bare paths are kept for the clients written before the prefix, and will be removed in the next release
+fn api_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_jobs)
+        .service(post_jobs_batch)
+        .service(get_jobs)
+        .service(get_jobs_by_id)
+        .service(get_jobs_cases)
+        .service(get_jobs_source)
+        .service(put_jobs_by_id)
+        .service(post_jobs_verify)
+        .service(post_jobs_kill)
+        .service(get_judge_queue)
+        .service(post_users)
+        .service(post_users_password)
+        .service(post_auth_login)
+        .service(get_users)
+        .service(get_users_solved)
+        .service(get_languages)
+        .service(get_problems_by_id)
+        .service(get_problems_samples)
+        .service(post_contests)
+        .service(get_contests_by_id)
+        .service(get_contests)
+        .service(get_contests_ranklist)
+        .service(get_contests_submissions)
+        .service(get_contests_announcements)
+        .service(post_contests_announcements)
+        .service(get_version);
+}
+
 //Used in automatic testing
 #[post("/internal/exit")]
 #[allow(unreachable_code)]
@@ -1570,50 +3214,143 @@ async fn main() -> io::Result<()> {
         }
     }
 
+    for problem in &config.problems {
+        if problem
+            .cases
+            .iter()
+            .any(|case| case.input_generator.as_ref().is_some_and(Vec::is_empty))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Empty input generator in problem {}", problem.id),
+            ));
+        }
+        for name in problem.misc.allowed_languages.iter().flatten() {
+            if !config
+                .languages
+                .iter()
+                .any(|lang| lang.full_name() == *name)
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Unknown language {} allowed in problem {}",
+                        name, problem.id
+                    ),
+                ));
+            }
+        }
+        let grouped = problem
+            .misc
+            .groups
+            .iter()
+            .flat_map(|group| &group.cases)
+            .collect::<Vec<_>>();
+        for id in &grouped {
+            if !(1..=problem.cases.len()).contains(id)
+                || grouped.iter().filter(|other| other == &id).count() > 1
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Case {} of problem {} is missing or in more than one group",
+                        id, problem.id
+                    ),
+                ));
+            }
+        }
+        if args.strict_config {
+            let total: f32 = problem.cases.iter().map(|case| case.score).sum();
+            if (total - 100.0).abs() > 1e-3 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Case scores of problem {} sum to {} instead of 100",
+                        problem.id, total
+                    ),
+                ));
+            }
+        }
+        for case in problem.public_cases() {
+            if case.input_generator.is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Sample case of problem {} has an input generator",
+                        problem.id
+                    ),
+                ));
+            }
+            for file in [&case.input_file, &case.answer_file]
+                .into_iter()
+                .chain(&case.answer_files)
+            {
+                if !std::path::Path::new(file).is_file() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Sample file {} of problem {} missing", file, problem.id),
+                    ));
+                }
+            }
+        }
+    }
+
     for l1 in &config.languages {
         if config
             .languages
             .iter()
-            .filter(|l2| l1.name == l2.name)
+            .filter(|l2| l1.full_name() == l2.full_name())
             .count()
             > 1
         {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                "Conflicting problem ID",
+                "Conflicting language name",
             ));
         }
+        if let Some(problem) = l1.check() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, problem));
+        }
     }
 
     //Flushes the data if required
     if args.flush_data {
         let _ = fs::remove_file("oj.db");
+        let _ = fs::remove_file("oj.db-wal");
+        let _ = fs::remove_file("oj.db-shm");
     }
 
     //Initializes database
-    let manager = SqliteConnectionManager::file("oj.db");
+    let manager = SqliteConnectionManager::file("oj.db").with_init(connection_init);
     let pool = Pool::new(manager).unwrap();
     database_init(&pool).unwrap();
 
+    //Dumps or restores the data without starting the server
+    if let Some(ref filename) = args.dump {
+        return dump_database(filename, &pool).map_err(|e| io::Error::other(e.to_string()));
+    }
+    if let Some(ref filename) = args.restore {
+        return restore_database(filename, &pool).map_err(|e| io::Error::other(e.to_string()));
+    }
+
     //Cleans up
     let _ = fs::remove_dir_all("temp");
 
+    //Initializes the judge queue shared by all the workers
+    let queue = web::Data::new(JudgeQueue::new(config.server.max_workers));
+
     //Starts the server
     HttpServer::new(move || {
         App::new()
             .wrap(Logger::default())
+            //Compresses the responses for clients accepting it, the request bodies
+            //with Content-Encoding are decompressed by the extractors
+            .wrap(Compress::default())
             .app_data(web::Data::new(config.clone()))
             .app_data(web::Data::new(pool.clone()))
-            .service(post_jobs)
-            .service(get_jobs)
-            .service(get_jobs_by_id)
-            .service(put_jobs_by_id)
-            .service(post_users)
-            .service(get_users)
-            .service(post_contests)
-            .service(get_contests_by_id)
-            .service(get_contests)
-            .service(get_contests_ranklist)
+            .app_data(queue.clone())
+            .service(web::scope(&format!("/api/{}", API_VERSION)).configure(api_routes))
+            .configure(api_routes)
             //Used in automatic testing
             .service(exit)
     })
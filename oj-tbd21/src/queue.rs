@@ -0,0 +1,253 @@
+use super::*;
+use actix_web::rt::task::JoinError;
+use std::collections::BTreeSet;
+use std::process::Child;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering as AtomicOrdering},
+    Mutex,
+};
+use tokio::sync::Semaphore;
+
+///Maximum number of active job ids reported by the queue status
+const ACTIVE_JOBS_SHOWN: usize = 10;
+
+///Jobs being judged and those of them asked to be killed by POST /jobs/{jobId}/kill
+pub static KILL_REQUESTS: KillRequests = KillRequests(Mutex::new(JudgedJobs {
+    running: BTreeSet::new(),
+    killed: BTreeSet::new(),
+}));
+
+///Ids of the jobs being judged and of the running jobs to be killed
+///Both sets are behind the same lock, so a job can't be asked to be killed once its worker is done with it
+pub struct KillRequests(Mutex<JudgedJobs>);
+
+///Sets of job ids kept by KillRequests
+struct JudgedJobs {
+    running: BTreeSet<usize>,
+    killed: BTreeSet<usize>,
+}
+
+impl KillRequests {
+    ///Forgets any request left for the job, e.g. before it is judged again
+    pub fn clear(&self, job_id: usize) {
+        self.0.lock().unwrap().killed.remove(&job_id);
+    }
+
+    ///Marks the job as being judged by a worker
+    pub fn start(&self, job_id: usize) {
+        self.0.lock().unwrap().running.insert(job_id);
+    }
+
+    ///Asks the worker judging the job to kill it
+    ///Returns: bool -- whether the job is being judged, nothing is asked otherwise
+    pub fn request(&self, job_id: usize) -> bool {
+        let mut jobs = self.0.lock().unwrap();
+        if !jobs.running.contains(&job_id) {
+            return false;
+        }
+        jobs.killed.insert(job_id);
+        true
+    }
+
+    ///Checks whether the job has been asked to be killed
+    pub fn requested(&self, job_id: usize) -> bool {
+        self.0.lock().unwrap().killed.contains(&job_id)
+    }
+
+    ///Marks the job as no longer judged and forgets the request once the worker is done with it
+    ///Returns: bool -- whether the job had been asked to be killed
+    pub fn finish(&self, job_id: usize) -> bool {
+        let mut jobs = self.0.lock().unwrap();
+        jobs.running.remove(&job_id);
+        jobs.killed.remove(&job_id)
+    }
+}
+
+///Error of a judge stopped by a kill request
+#[derive(Debug)]
+pub struct JobKilled(pub usize);
+
+impl std::fmt::Display for JobKilled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Job {} killed", self.0)
+    }
+}
+
+impl Error for JobKilled {}
+
+///Kills the child and removes the temporary directory of the run if the job has been asked to be killed
+pub fn check_killed(
+    job_id: usize,
+    child: &mut Child,
+    temp_dir: &str,
+) -> Result<(), Box<dyn Error>> {
+    if KILL_REQUESTS.requested(job_id) {
+        let _ = child.kill();
+        let _ = child.wait();
+        fs::remove_dir_all(temp_dir)?;
+        return Err(Box::new(JobKilled(job_id)));
+    }
+    Ok(())
+}
+
+///Queue of the jobs waiting to be judged
+///At most max_workers jobs are judged at the same time, the others wait for a permit
+pub struct JudgeQueue {
+    semaphore: Arc<Semaphore>,
+    max_workers: usize,
+    pending: AtomicUsize,
+    active_jobs: Mutex<Vec<usize>>,
+}
+
+///Body of judge queue status response
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct QueueStatus {
+    pending: usize,
+    active: usize,
+
+    ///Number of idle judge workers
+    workers: usize,
+    max_workers: usize,
+
+    ///Ids of the most recently started active jobs
+    active_jobs: Vec<usize>,
+}
+
+impl JudgeQueue {
+    ///Makes a new JudgeQueue
+    pub fn new(max_workers: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_workers)),
+            max_workers,
+            pending: AtomicUsize::new(0),
+            active_jobs: Mutex::new(vec![]),
+        }
+    }
+
+    ///Gets the current status of the queue
+    pub fn status(&self) -> QueueStatus {
+        let workers = self.semaphore.available_permits();
+        QueueStatus {
+            pending: self.pending.load(AtomicOrdering::SeqCst),
+            active: self.max_workers - workers,
+            workers,
+            max_workers: self.max_workers,
+            active_jobs: self
+                .active_jobs
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .take(ACTIVE_JOBS_SHOWN)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    ///Puts a queueing job into the queue, the job is updated in the SQLite database once judged
+    pub fn push(
+        self: Arc<Self>,
+        job: Job,
+        config: Arc<Config>,
+        contest: Option<Contest>,
+        pool: Arc<Pool<SqliteConnectionManager>>,
+        compile_only: bool,
+    ) {
+        //A request left by a kill that came too late must not stop the job this time
+        KILL_REQUESTS.clear(job.id);
+        self.pending.fetch_add(1, AtomicOrdering::SeqCst);
+        actix_web::rt::spawn(async move {
+            let id = job.id;
+            let _ = self
+                .run_pending(id, move || {
+                    run_job(job, config, contest.as_ref(), &pool, compile_only)
+                })
+                .await;
+        });
+    }
+
+    ///Judges on a worker outside of the stored jobs, e.g. to verify a job, waiting for an idle one like the queued jobs
+    ///Returns: Result<T, JoinError> -- the result of the judge, the error if the blocking thread panicked
+    pub async fn run<T, F>(&self, job_id: usize, f: F) -> Result<T, JoinError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        self.pending.fetch_add(1, AtomicOrdering::SeqCst);
+        self.run_pending(job_id, f).await
+    }
+
+    ///Waits for an idle worker for a judge already counted as pending, then judges on a blocking thread
+    ///The job is shown among the active jobs while it is judged
+    async fn run_pending<T, F>(&self, job_id: usize, f: F) -> Result<T, JoinError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let _permit = self.semaphore.acquire().await.unwrap();
+        self.pending.fetch_sub(1, AtomicOrdering::SeqCst);
+        self.active_jobs.lock().unwrap().push(job_id);
+
+        let result = actix_web::rt::task::spawn_blocking(f).await;
+
+        //The same job may be judged twice at once, e.g. verified while judged again
+        let mut active_jobs = self.active_jobs.lock().unwrap();
+        if let Some(index) = active_jobs.iter().position(|active| *active == job_id) {
+            active_jobs.remove(index);
+        }
+        result
+    }
+}
+
+///Judges a queueing job and stores the result
+fn run_job(
+    mut job: Job,
+    config: Arc<Config>,
+    contest: Option<&Contest>,
+    pool: &Pool<SqliteConnectionManager>,
+    compile_only: bool,
+) {
+    log::info!(target: "judge_queue", "Judging job {}", job.id);
+
+    job.state = OjState::Running;
+    job.result = OjResult::Running;
+    job.updated_time = UtcDateTime { time: Utc::now() };
+    if let Err(e) = job.update(pool) {
+        log::error!(target: "judge_queue", "Failed to update job {}: {}", job.id, e);
+    }
+    KILL_REQUESTS.start(job.id);
+
+    let judged = match judge(
+        job.id,
+        &job.submission,
+        config,
+        contest,
+        job.created_time,
+        job.updated_time,
+        compile_only,
+    ) {
+        Ok(judged) => judged,
+        //The job has already been stored as canceled by the kill request
+        Err(e) if e.is::<JobKilled>() => {
+            KILL_REQUESTS.finish(job.id);
+            log::info!(target: "judge_queue", "{}", e);
+            return;
+        }
+        Err(e) => {
+            log::error!(target: "judge_queue", "Failed to judge job {}: {}", job.id, e);
+            Job {
+                state: OjState::Finished,
+                result: OjResult::SystemError,
+                ..job
+            }
+        }
+    };
+
+    //A job killed right after being judged stays canceled, later kill requests are refused
+    if KILL_REQUESTS.finish(judged.id) {
+        return;
+    }
+    if let Err(e) = judged.update(pool) {
+        log::error!(target: "judge_queue", "Failed to update job {}: {}", judged.id, e);
+    }
+}
@@ -0,0 +1,41 @@
+use super::*;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+};
+
+///Times of the recent submissions of each user, shared by all workers
+pub static SUBMISSION_RATES: SubmissionRates = SubmissionRates::new();
+
+///Sliding window of the submissions of each user
+pub struct SubmissionRates {
+    submitted: Mutex<BTreeMap<usize, VecDeque<Instant>>>,
+}
+
+impl SubmissionRates {
+    ///Makes an empty SubmissionRates
+    const fn new() -> Self {
+        Self {
+            submitted: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    ///Records a submission of the user unless it has made limit submissions within the window
+    ///Returns: bool -- whether the submission is allowed
+    pub fn try_submit(&self, user_id: usize, limit: usize, window: Duration) -> bool {
+        let now = Instant::now();
+        let mut submitted = self.submitted.lock().unwrap();
+        let times = submitted.entry(user_id).or_default();
+        while times
+            .front()
+            .is_some_and(|time| now.duration_since(*time) >= window)
+        {
+            times.pop_front();
+        }
+        if times.len() >= limit {
+            return false;
+        }
+        times.push_back(now);
+        true
+    }
+}
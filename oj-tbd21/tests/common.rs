@@ -108,6 +108,19 @@ struct HttpComm {
     restart_server: bool, // restart server before sending request
 }
 
+/// Runs the OJ with the given arguments until it exits, returns whether it succeeded.
+/// Used for the subcommands which do not start the server (e.g. `--dump`).
+#[allow(dead_code)]
+pub fn run_oj(args: &[&str]) -> bool {
+    Command::new(EXE_PATH.as_os_str())
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .expect("failed to execute OJ process")
+        .success()
+}
+
 pub struct TestCase {
     name: String,
     arguments: Vec<String>,
@@ -152,6 +165,14 @@ impl TestCase {
         }
     }
 
+    /// Keeps the data of the previous server instead of flushing it on startup.
+    /// Used for checks across servers started with different configurations.
+    #[allow(dead_code)]
+    pub fn keep_data(mut self) -> Self {
+        self.arguments.retain(|x| x != "--flush-data");
+        self
+    }
+
     fn log_and_send(
         &self,
         req: RequestBuilder,
@@ -368,6 +389,22 @@ impl TestCase {
         body
     }
 
+    /// Runs the sequence of requests, then calls `f` with the server prefix before shutting it down.
+    /// Used for checks that can not be expressed as sequential requests (e.g. concurrent ones).
+    #[allow(dead_code)]
+    pub fn run_then<F: FnOnce(&str)>(&mut self, f: F) -> Vec<Value> {
+        self.start_server(false);
+        let res = self
+            .data
+            .clone()
+            .iter()
+            .map(|d| self.send_request_and_compare_response(d))
+            .collect();
+        f(&self.prefix);
+        self.kill_server();
+        res
+    }
+
     pub fn run(&mut self) -> Vec<Value> {
         self.start_server(false);
         // send requests sequentially
@@ -0,0 +1,1081 @@
+mod common;
+use common::{run_oj, TestCase};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::io::{Read, Write};
+
+#[test]
+fn test_ext_01_concurrent_user_creation() {
+    // create users from several threads at the same time
+    // check that every user gets a distinct id
+    TestCase::read("ext_01_concurrent_users").run_then(|prefix| {
+        let handles = (0..8)
+            .map(|i| {
+                let url = format!("{}/users", prefix);
+                std::thread::spawn(move || {
+                    let resp = Client::new()
+                        .post(&url)
+                        .json(&json!({ "name": format!("user{}", i) }))
+                        .send()
+                        .unwrap();
+                    assert_eq!(
+                        resp.status().as_u16(),
+                        200,
+                        "case ext_01_concurrent_users incorrect: wrong status code"
+                    );
+                    resp.json::<Value>().unwrap()["id"].as_u64().unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        let ids = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            ids,
+            (1..=8).collect::<BTreeSet<_>>(),
+            "case ext_01_concurrent_users incorrect: user ids are not distinct"
+        );
+    });
+}
+
+#[test]
+fn test_ext_02_language_version() {
+    // two language entries share the same name but differ in version
+    // check that the full name (with version) selects the language
+    // and that the command of the language is recorded on the job
+    TestCase::read("ext_02_language_version").run();
+}
+
+#[test]
+fn test_ext_03_contest_limit_override() {
+    // a contest overrides the time limit of a problem
+    // check that the override only applies to submissions in the contest
+    TestCase::read("ext_03_contest_limit_override").run();
+}
+
+#[test]
+fn test_ext_04_judge_queue() {
+    // submit several slow jobs with a single judge worker
+    // check the queue depth while judging and after all jobs are finished
+    TestCase::read("ext_04_judge_queue").run();
+}
+
+#[test]
+fn test_ext_05_announcements() {
+    // only root can make announcements in a contest
+    // check that announcements are listed from the latest
+    TestCase::read("ext_05_announcements").run();
+}
+
+#[test]
+fn test_ext_06_job_cases() {
+    // check that only the case results are returned, optionally filtered by result
+    TestCase::read("ext_06_job_cases").run();
+}
+
+#[test]
+fn test_ext_07_contest_submissions() {
+    // check the selected job of each user on each problem under different scoring rules
+    TestCase::read("ext_07_contest_submissions").run();
+}
+
+#[test]
+fn test_ext_08_password_login() {
+    // set a password for a user and log in with it
+    // check that submissions on behalf of the user require a valid, unexpired token
+    TestCase::read("ext_08_password_login").run_then(|prefix| {
+        let client = Client::new();
+        let job = json!({
+            "source_code": "fn main() { println!(\"Hello World!\"); }",
+            "language": "Rust",
+            "user_id": 1,
+            "contest_id": 0,
+            "problem_id": 0
+        });
+
+        let resp = client
+            .post(format!("{}/auth/login", prefix))
+            .json(&json!({ "user_id": 1, "password": "secret" }))
+            .send()
+            .unwrap();
+        assert_eq!(
+            resp.status().as_u16(),
+            200,
+            "case ext_08_password_login incorrect: failed to log in"
+        );
+        let token = resp.json::<Value>().unwrap()["token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let submit = |token: &str| {
+            client
+                .post(format!("{}/jobs", prefix))
+                .bearer_auth(token)
+                .json(&job)
+                .send()
+                .unwrap()
+                .status()
+                .as_u16()
+        };
+        assert_eq!(
+            submit(&token),
+            200,
+            "case ext_08_password_login incorrect: valid token rejected"
+        );
+        assert_eq!(
+            submit("invalid"),
+            401,
+            "case ext_08_password_login incorrect: invalid token accepted"
+        );
+
+        // the token lifetime is 2 seconds in the config
+        std::thread::sleep(std::time::Duration::from_secs(3));
+        assert_eq!(
+            submit(&token),
+            401,
+            "case ext_08_password_login incorrect: expired token accepted"
+        );
+    });
+}
+
+#[test]
+fn test_ext_09_concurrent_jobs() {
+    // submit many jobs and create users from several threads at the same time
+    // check that no request fails because the database is locked
+    TestCase::read("ext_09_concurrent_jobs").run_then(|prefix| {
+        let handles = (0..32)
+            .map(|i| {
+                let prefix = prefix.to_string();
+                std::thread::spawn(move || {
+                    let client = Client::new();
+                    let resp = if i % 4 == 0 {
+                        client
+                            .post(format!("{}/users", prefix))
+                            .json(&json!({ "name": format!("stress{}", i) }))
+                            .send()
+                            .unwrap()
+                    } else {
+                        client
+                            .post(format!("{}/jobs", prefix))
+                            .json(&json!({
+                                "source_code": "fn main() { println!(\"Hello World!\"); }",
+                                "language": "Rust",
+                                "user_id": i % 2,
+                                "contest_id": 0,
+                                "problem_id": 0
+                            }))
+                            .send()
+                            .unwrap()
+                    };
+                    let status = resp.status().as_u16();
+                    let body = resp.json::<Value>().unwrap();
+                    assert_eq!(
+                        status, 200,
+                        "case ext_09_concurrent_jobs incorrect: request failed with {}",
+                        body
+                    );
+                    (i % 4 == 0, body["id"].as_u64().unwrap())
+                })
+            })
+            .collect::<Vec<_>>();
+        let job_ids = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|(is_user, _)| !is_user)
+            .map(|(_, id)| id)
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            job_ids,
+            (0..24).collect::<BTreeSet<_>>(),
+            "case ext_09_concurrent_jobs incorrect: job ids are not distinct"
+        );
+    });
+}
+
+#[test]
+fn test_ext_10_sample_cases() {
+    // check that the sample cases and their contents are public
+    // while the judging cases are not revealed
+    let res = TestCase::read("ext_10_sample_cases").run();
+    assert!(
+        res[0].get("cases").is_none(),
+        "case ext_10_sample_cases incorrect: judging cases revealed"
+    );
+}
+
+#[test]
+fn test_ext_11_total_judge_time() {
+    // each case runs for 2 seconds while the whole submission may only take 4 seconds
+    // check that the case running out of budget is stopped and the rest are skipped
+    TestCase::read("ext_11_total_judge_time").run();
+}
+
+#[test]
+fn test_ext_12_per_problem_submission_limit() {
+    // a contest of two problems with submission limits [10, 3]
+    // check that each problem is limited separately instead of by submission_limit
+    TestCase::read("ext_12_per_problem_submission_limit").run();
+}
+
+#[test]
+fn test_ext_13_hide_inactive() {
+    // only user 2 submits in the global contest
+    // check that the other users are dropped from the ranklist only when asked to
+    let res = TestCase::read("ext_13_hide_inactive").run();
+    assert_eq!(
+        res[2].as_array().unwrap().len(),
+        0,
+        "case ext_13_hide_inactive incorrect: inactive users shown"
+    );
+    assert_eq!(
+        res[4].as_array().unwrap().len(),
+        1,
+        "case ext_13_hide_inactive incorrect: inactive users shown"
+    );
+}
+
+#[test]
+fn test_ext_14_config_version() {
+    // judge a job, then restart the server with an extra case added to the problem
+    // check that rejudging is refused unless forced, and then uses the new cases
+    TestCase::read("ext_14_config_version_before").run();
+    TestCase::read("ext_14_config_version_after")
+        .keep_data()
+        .run();
+}
+
+#[test]
+fn test_ext_15_rfc3339_timestamps() {
+    // send contest times and job filter bounds with timezone offsets
+    // check that they are normalized to UTC and still serialized in the canonical format
+    let res = TestCase::read("ext_15_rfc3339_timestamps").run();
+    assert_eq!(
+        res[3].as_array().unwrap().len(),
+        0,
+        "case ext_15_rfc3339_timestamps incorrect: wrong filter bound"
+    );
+}
+
+#[test]
+fn test_ext_16_fail_fast() {
+    // each run takes 0.5 second and gives a wrong answer on a problem of 10 cases
+    // check that the later cases are skipped without being run
+    TestCase::read("ext_16_fail_fast").run();
+}
+
+#[test]
+fn test_ext_17_batch_submission() {
+    // submit a batch mixing valid and invalid submissions
+    // check that job ids are returned in order and errors are reported per submission
+    let res = TestCase::read("ext_17_batch_submission").run();
+    assert!(
+        res[0][1].get("job_id").is_none() && res[0][2].get("job_id").is_none(),
+        "case ext_17_batch_submission incorrect: invalid submissions accepted"
+    );
+}
+
+#[test]
+fn test_ext_18_normalize_newlines() {
+    // the answer of a strict problem ends with CRLF while the program may print LF
+    // check that only line endings are normalized, and only when enabled
+    TestCase::read("ext_18_normalize_newlines").run();
+}
+
+#[test]
+fn test_ext_19_cpu_time_limit() {
+    // a busy loop is stopped by the CPU time limit well before the wall-clock limit
+    // check that sleeping does not count against the CPU time limit
+    TestCase::read("ext_19_cpu_time_limit").run();
+}
+
+#[test]
+fn test_ext_20_user_filter() {
+    // search users by a substring of their names and page through the results
+    // check that wildcards in the name are matched literally
+    TestCase::read("ext_20_user_filter").run_then(|prefix| {
+        let names = |query: &str| {
+            let resp = Client::new()
+                .get(format!("{}/users?{}", prefix, query))
+                .send()
+                .unwrap();
+            assert_eq!(
+                resp.status().as_u16(),
+                200,
+                "case ext_20_user_filter incorrect: wrong status code"
+            );
+            resp.json::<Vec<Value>>()
+                .unwrap()
+                .iter()
+                .map(|user| user["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("name=al"), ["alice", "alfred", "Alan"]);
+        assert_eq!(names("name=al&limit=2"), ["alice", "alfred"]);
+        assert_eq!(names("name=al&limit=2&offset=2"), ["Alan"]);
+        assert_eq!(names("offset=5"), ["Alan"]);
+        assert_eq!(names("name=_"), ["a_b"]);
+        assert_eq!(names("name=%25"), Vec::<String>::new());
+        assert_eq!(names("").len(), 6);
+    });
+}
+
+#[test]
+fn test_ext_21_error_messages() {
+    // request the same error in different languages
+    // check that the message follows Accept-Language while the code stays the same
+    TestCase::read("ext_21_error_messages").run_then(|prefix| {
+        let error = |accept_language: &str| {
+            let resp = Client::new()
+                .get(format!("{}/problems/99", prefix))
+                .header("Accept-Language", accept_language)
+                .send()
+                .unwrap();
+            assert_eq!(
+                resp.status().as_u16(),
+                404,
+                "case ext_21_error_messages incorrect: wrong status code"
+            );
+            let body = resp.json::<Value>().unwrap();
+            assert_eq!(body["code"], 3);
+            assert_eq!(body["reason"], "ERR_NOT_FOUND");
+            body["message"].as_str().unwrap().to_string()
+        };
+        assert_eq!(error("zh-CN"), "题目 99 不存在。");
+        assert_eq!(error("fr, zh;q=0.8, en;q=0.5"), "题目 99 不存在。");
+        assert_eq!(error("zh;q=0.3, en-US"), "Problem 99 not found.");
+        assert_eq!(error("fr"), "Problem 99 not found.");
+    });
+}
+
+#[test]
+fn test_ext_22_verify() {
+    // judge a finished job again and compare the verdicts
+    // check that the stored job is not changed by the verification
+    TestCase::read("ext_22_verify").run_then(|prefix| {
+        let client = Client::new();
+        let job = || {
+            client
+                .get(format!("{}/jobs/0", prefix))
+                .send()
+                .unwrap()
+                .json::<Value>()
+                .unwrap()
+        };
+        let before = job();
+        let resp = client
+            .post(format!("{}/jobs/0/verify", prefix))
+            .send()
+            .unwrap();
+        assert_eq!(
+            resp.status().as_u16(),
+            200,
+            "case ext_22_verify incorrect: wrong status code"
+        );
+        assert_eq!(
+            before,
+            job(),
+            "case ext_22_verify incorrect: the stored job is changed"
+        );
+
+        // once root has a password, only root can verify a job
+        client
+            .post(format!("{}/users/0/password", prefix))
+            .json(&json!({ "password": "admin" }))
+            .send()
+            .unwrap();
+        let resp = client
+            .post(format!("{}/jobs/0/verify", prefix))
+            .send()
+            .unwrap();
+        assert_eq!(
+            resp.status().as_u16(),
+            401,
+            "case ext_22_verify incorrect: verified a job without a token of root"
+        );
+        let token = client
+            .post(format!("{}/auth/login", prefix))
+            .json(&json!({ "user_id": 0, "password": "admin" }))
+            .send()
+            .unwrap()
+            .json::<Value>()
+            .unwrap()["token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let resp = client
+            .post(format!("{}/jobs/0/verify", prefix))
+            .bearer_auth(&token)
+            .send()
+            .unwrap();
+        assert_eq!(
+            resp.status().as_u16(),
+            200,
+            "case ext_22_verify incorrect: wrong status code with a token of root"
+        );
+    });
+}
+
+#[test]
+fn test_ext_23_dump_and_restore() {
+    // dump the data of a server, then restore it into a fresh database
+    // check that the restored server shows the same data with the same ids, announcements included
+    const CONFIG: &str = "tests/cases/ext_23_dump.config.json";
+    const BACKUP: &str = "tests/cases/ext_23_dump.backup.json";
+    let fetch = |prefix: &str| {
+        ["users", "contests", "jobs", "contests/1/announcements"]
+            .iter()
+            .map(|path| {
+                Client::new()
+                    .get(format!("{}/{}", prefix, path))
+                    .send()
+                    .unwrap()
+                    .json::<Value>()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut dumped = Vec::new();
+    TestCase::read("ext_23_dump").run_then(|prefix| {
+        dumped = fetch(prefix);
+        assert!(
+            run_oj(&["--config", CONFIG, "--dump", BACKUP]),
+            "case ext_23_dump incorrect: dump failed"
+        );
+    });
+
+    // restoring into a database which is not fresh is refused
+    assert!(
+        !run_oj(&["--config", CONFIG, "--restore", BACKUP]),
+        "case ext_23_restore incorrect: restored into a non-empty database"
+    );
+    assert!(
+        run_oj(&["--config", CONFIG, "--flush-data", "--restore", BACKUP]),
+        "case ext_23_restore incorrect: restore failed"
+    );
+
+    // the restored password still works
+    TestCase::read("ext_23_restore")
+        .keep_data()
+        .run_then(|prefix| {
+            assert_eq!(
+                dumped,
+                fetch(prefix),
+                "case ext_23_restore incorrect: restored data differs"
+            );
+            // new users get ids after the restored ones
+            let user = Client::new()
+                .post(format!("{}/users", prefix))
+                .json(&json!({ "name": "user3" }))
+                .send()
+                .unwrap()
+                .json::<Value>()
+                .unwrap();
+            assert_eq!(user["id"], 3);
+        });
+}
+
+#[test]
+fn test_ext_24_gzip() {
+    // post a gzip-compressed submission and ask for a gzip-compressed response
+    // check that the job is the same as an uncompressed one
+    TestCase::read("ext_24_gzip").run_then(|prefix| {
+        let submission = json!({
+            "source_code": "fn main() { println!(\"Hello World!\"); }",
+            "language": "Rust",
+            "user_id": 0,
+            "contest_id": 0,
+            "problem_id": 0
+        });
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(submission.to_string().as_bytes())
+            .unwrap();
+        let resp = Client::new()
+            .post(format!("{}/jobs", prefix))
+            .header("Content-Type", "application/json")
+            .header("Content-Encoding", "gzip")
+            .header("Accept-Encoding", "gzip")
+            .body(encoder.finish().unwrap())
+            .send()
+            .unwrap();
+        assert_eq!(
+            resp.status().as_u16(),
+            200,
+            "case ext_24_gzip incorrect: wrong status code"
+        );
+        assert_eq!(
+            resp.headers()["Content-Encoding"],
+            "gzip",
+            "case ext_24_gzip incorrect: response not compressed"
+        );
+        let mut body = String::new();
+        GzDecoder::new(&resp.bytes().unwrap()[..])
+            .read_to_string(&mut body)
+            .unwrap();
+        let job: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(job["id"], 0);
+        assert_eq!(job["submission"], submission);
+    });
+}
+
+#[test]
+fn test_ext_25_remaining_submissions() {
+    // submit to a contest until its submission limit is reached
+    // check that the number of remaining submissions counts down to zero
+    TestCase::read("ext_25_remaining_submissions").run_then(|prefix| {
+        // submissions outside contests have no limit to report
+        let job = Client::new()
+            .post(format!("{}/jobs", prefix))
+            .json(&json!({
+                "source_code": "fn main() { println!(\"Hello World!\"); }",
+                "language": "Rust",
+                "user_id": 1,
+                "contest_id": 0,
+                "problem_id": 0
+            }))
+            .send()
+            .unwrap()
+            .json::<Value>()
+            .unwrap();
+        assert_eq!(job["id"], 2);
+        assert!(
+            job.get("remaining_submissions").is_none(),
+            "case ext_25_remaining_submissions incorrect: remaining submissions outside contests"
+        );
+    });
+}
+
+#[test]
+fn test_ext_26_multiple_answers() {
+    // a case with two answer files accepts an output matching either of them
+    // check both the standard and the strict comparison
+    TestCase::read("ext_26_multiple_answers").run();
+}
+
+#[test]
+fn test_ext_27_java() {
+    // the source file is named after its public class, "Main" if there is none
+    // check that Java submissions compile and run with either name
+    TestCase::read("ext_27_java").run();
+
+    // a command which does not reference the output file is refused on startup
+    assert!(
+        !run_oj(&[
+            "--config",
+            "tests/cases/ext_27_invalid_language.config.json"
+        ]),
+        "case ext_27_invalid_language incorrect: invalid language accepted"
+    );
+}
+
+#[test]
+fn test_ext_28_ranklist_cache() {
+    // load the same ranklist repeatedly while submitting and renaming a user
+    // check that the cached ranklist is dropped whenever a job or a user changes
+    TestCase::read("ext_28_ranklist_cache").run();
+}
+
+#[test]
+fn test_ext_29_compile_only() {
+    // submit with compile_only, the program leaves a file behind once it runs
+    // check that only the compilation has a result and the program is never run
+    const RAN: &str = "tests/cases/ext_29_compile_only.ran";
+    let _ = std::fs::remove_file(RAN);
+    TestCase::read("ext_29_compile_only").run();
+    assert!(
+        !std::path::Path::new(RAN).exists(),
+        "case ext_29_compile_only incorrect: the program was run"
+    );
+}
+
+#[test]
+fn test_ext_30_input_generator() {
+    // the input of each case is printed by a generator given a seed
+    // check that a failed generator is a system error of its case only
+    TestCase::read("ext_30_input_generator").run();
+}
+
+#[test]
+fn test_ext_31_solved_problems() {
+    // list the problems solved by a user with the earliest accepted job
+    // check that wrong answers and later accepted jobs are ignored
+    TestCase::read("ext_31_solved_problems").run();
+}
+
+#[test]
+fn test_ext_32_binary_output() {
+    // compare the raw bytes of the output, which contains null bytes and is not valid UTF-8
+    // check that a trailing newline is not trimmed from the answer
+    TestCase::read("ext_32_binary_output").run();
+}
+
+#[test]
+fn test_ext_33_allowed_languages() {
+    // a problem only accepting C++ refuses a Rust submission
+    // check that the other problems still accept every language
+    TestCase::read("ext_33_allowed_languages").run();
+
+    // only configured languages can be allowed
+    assert!(
+        !run_oj(&[
+            "--config",
+            "tests/cases/ext_33_unknown_allowed_language.config.json"
+        ]),
+        "case ext_33_unknown_allowed_language incorrect: unknown language accepted"
+    );
+}
+
+#[test]
+fn test_ext_34_job_source() {
+    // get the source code of a job as plain text
+    // check that it is not escaped and is named after the language's file name
+    TestCase::read("ext_34_job_source").run_then(|prefix| {
+        let resp = Client::new()
+            .get(format!("{}/jobs/0/source", prefix))
+            .send()
+            .unwrap();
+        assert_eq!(
+            resp.status().as_u16(),
+            200,
+            "case ext_34_job_source incorrect: wrong status code"
+        );
+        assert!(
+            resp.headers()["Content-Type"]
+                .to_str()
+                .unwrap()
+                .starts_with("text/plain"),
+            "case ext_34_job_source incorrect: wrong content type"
+        );
+        assert_eq!(
+            resp.headers()["Content-Disposition"],
+            "inline; filename=\"main.rs\"",
+            "case ext_34_job_source incorrect: wrong file name"
+        );
+        assert_eq!(
+            resp.text().unwrap(),
+            "fn main() {\n    // \"quoted\" and <tagged>\n    println!(\"Hello World!\");\n}\n",
+            "case ext_34_job_source incorrect: wrong source code"
+        );
+    });
+}
+
+#[test]
+fn test_ext_35_whitespace_report() {
+    // submit outputs which are wrong only in whitespace, or not
+    // check that the info of the wrong answer notes it unless disabled
+    TestCase::read("ext_35_whitespace_report").run();
+}
+
+#[test]
+fn test_ext_36_ranklist_csv() {
+    // export the ranklist of a contest as CSV
+    // check that the problem columns follow the contest and the names are quoted if needed
+    TestCase::read("ext_36_ranklist_csv").run_then(|prefix| {
+        let resp = Client::new()
+            .get(format!("{}/contests/1/ranklist?format=csv", prefix))
+            .send()
+            .unwrap();
+        assert_eq!(
+            resp.status().as_u16(),
+            200,
+            "case ext_36_ranklist_csv incorrect: wrong status code"
+        );
+        assert!(
+            resp.headers()["Content-Type"]
+                .to_str()
+                .unwrap()
+                .starts_with("text/csv"),
+            "case ext_36_ranklist_csv incorrect: wrong content type"
+        );
+        assert_eq!(
+            resp.text().unwrap(),
+            "rank,user_id,user_name,total_score,problem_0,problem_1\n\
+             1,1,alice,200,100,100\n\
+             2,2,\"bob, \"\"jr\"\"\",100,0,100\n",
+            "case ext_36_ranklist_csv incorrect: wrong ranklist"
+        );
+
+        // the JSON ranklist is still the default
+        let ranklist: Value = Client::new()
+            .get(format!("{}/contests/1/ranklist", prefix))
+            .send()
+            .unwrap()
+            .json()
+            .unwrap();
+        assert_eq!(
+            ranklist[1]["scores"],
+            json!([0.0, 100.0]),
+            "case ext_36_ranklist_csv incorrect: wrong JSON ranklist"
+        );
+    });
+}
+
+#[test]
+#[cfg(unix)]
+fn test_ext_37_process_limit() {
+    // RLIMIT_NPROC is not enforced for root, so the threads would not be contained
+    if unsafe { libc::geteuid() } == 0 {
+        eprintln!("skipped: the process limit does not apply to root");
+        return;
+    }
+    // a program spawning more threads than allowed fails instead of hanging the judge
+    // check that a single-threaded program is not affected
+    TestCase::read("ext_37_process_limit").run();
+}
+
+#[test]
+fn test_ext_38_strict_config() {
+    // the case scores of problem 1 only sum to 90, which is allowed by default
+    // the dump exits right after the configuration is checked
+    const BACKUP: &str = "tests/cases/ext_38_strict_config.backup.json";
+    assert!(
+        run_oj(&[
+            "--config",
+            "tests/cases/ext_38_strict_config.config.json",
+            "--dump",
+            BACKUP
+        ]),
+        "case ext_38_strict_config incorrect: refused without --strict-config"
+    );
+    assert!(
+        !run_oj(&[
+            "--config",
+            "tests/cases/ext_38_strict_config.config.json",
+            "--strict-config",
+            "--dump",
+            BACKUP
+        ]),
+        "case ext_38_strict_config incorrect: scores not summing to 100 accepted"
+    );
+    assert!(
+        run_oj(&[
+            "--config",
+            "tests/cases/ext_28_ranklist_cache.config.json",
+            "--strict-config",
+            "--dump",
+            BACKUP
+        ]),
+        "case ext_38_strict_config incorrect: scores summing to 100 refused"
+    );
+}
+
+#[test]
+fn test_ext_39_sample_cases() {
+    // only the first case of the problem is a sample, both are judged
+    // check that the hidden case is not shown
+    TestCase::read("ext_39_sample_cases").run_then(|prefix| {
+        let samples: Value = Client::new()
+            .get(format!("{}/problems/1/samples", prefix))
+            .send()
+            .unwrap()
+            .json()
+            .unwrap();
+        assert_eq!(
+            samples.as_array().unwrap().len(),
+            1,
+            "case ext_39_sample_cases incorrect: hidden case shown"
+        );
+    });
+}
+
+#[test]
+fn test_ext_40_rate_limit() {
+    // each user can only submit twice within a minute, whatever the problem
+    // check that the submissions refused for other reasons do not count
+    TestCase::read("ext_40_rate_limit").run();
+}
+
+#[test]
+fn test_ext_41_api_version() {
+    // the handlers are served both under "/api/v1" and at the bare paths
+    TestCase::read("ext_41_api_version").run();
+}
+
+#[test]
+fn test_ext_42_missing_target() {
+    // the compile command of Bogus succeeds without writing the binary
+    TestCase::read("ext_42_missing_target").run();
+}
+
+#[test]
+fn test_ext_43_group_scoring() {
+    // the first submission only passes the odd cases, so each group is partially passed:
+    // 10 of the sum group, none of the all-or-nothing group, and 10 of each of the odd cases left
+    TestCase::read("ext_43_group_scoring").run();
+    // a case can't be scored in two groups
+    assert!(
+        !run_oj(&[
+            "--config",
+            "tests/cases/ext_43_group_overlap.config.json",
+            "--dump",
+            "tests/cases/ext_43_group_overlap.backup.json"
+        ]),
+        "case ext_43_group_scoring incorrect: overlapping groups accepted"
+    );
+}
+
+#[test]
+fn test_ext_44_kill_job() {
+    // the program sleeps far longer than the test waits, so the job is still running when killed
+    // only the administrator (root) can kill jobs, so root sets a password first
+    TestCase::read("ext_44_kill_job").run_then(|prefix| {
+        let client = Client::new();
+        let job = |client: &Client| {
+            client
+                .get(format!("{}/jobs/0", prefix))
+                .send()
+                .unwrap()
+                .json::<Value>()
+                .unwrap()
+        };
+        client
+            .post(format!("{}/users/0/password", prefix))
+            .json(&json!({ "password": "admin" }))
+            .send()
+            .unwrap();
+        let token = client
+            .post(format!("{}/auth/login", prefix))
+            .json(&json!({ "user_id": 0, "password": "admin" }))
+            .send()
+            .unwrap()
+            .json::<Value>()
+            .unwrap()["token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let kill = |client: &Client| {
+            client
+                .post(format!("{}/jobs/0/kill", prefix))
+                .bearer_auth(&token)
+                .send()
+                .unwrap()
+        };
+        for _ in 0..10 {
+            if job(&client)["state"] == "Running" {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+        let resp = client
+            .post(format!("{}/jobs/0/kill", prefix))
+            .send()
+            .unwrap();
+        assert_eq!(
+            resp.status().as_u16(),
+            401,
+            "case ext_44_kill_job incorrect: killed a job without a token of root"
+        );
+        let resp = kill(&client);
+        assert_eq!(
+            resp.status().as_u16(),
+            200,
+            "case ext_44_kill_job incorrect: failed to kill a running job"
+        );
+        assert_eq!(resp.json::<Value>().unwrap()["state"], "Canceled");
+
+        // the worker must not overwrite the canceled job when it stops judging
+        std::thread::sleep(std::time::Duration::from_secs(2));
+        assert_eq!(
+            job(&client)["state"],
+            "Canceled",
+            "case ext_44_kill_job incorrect: killed job not canceled"
+        );
+        let resp = kill(&client);
+        assert_eq!(
+            resp.status().as_u16(),
+            400,
+            "case ext_44_kill_job incorrect: killed a job that is not running"
+        );
+        assert_eq!(resp.json::<Value>().unwrap()["reason"], "ERR_INVALID_STATE");
+    });
+}
+
+#[test]
+fn test_ext_45_unicode_output() {
+    // the program prints combining accents while the answer has precomposed letters
+    // they only match once both are normalized, and the problem without the option is unchanged
+    TestCase::read("ext_45_unicode_output").run();
+}
+
+#[test]
+fn test_ext_46_time_zone() {
+    // the times of a job are shown in the time zone asked for, as the same instants as in UTC
+    TestCase::read("ext_46_time_zone").run_then(|prefix| {
+        let get = |path: &str| {
+            let resp = Client::new()
+                .get(format!("{}/{}", prefix, path))
+                .send()
+                .unwrap();
+            (resp.status().as_u16(), resp.json::<Value>().unwrap())
+        };
+        let time = |job: &Value, key: &str| {
+            chrono::DateTime::parse_from_rfc3339(job[key].as_str().unwrap()).unwrap()
+        };
+
+        let (_, utc) = get("jobs/0");
+        let (status, shanghai) = get("jobs/0?tz=Asia/Shanghai");
+        assert_eq!(
+            status, 200,
+            "case ext_46_time_zone incorrect: wrong status code"
+        );
+        for key in ["created_time", "updated_time"] {
+            assert!(
+                utc[key].as_str().unwrap().ends_with('Z'),
+                "case ext_46_time_zone incorrect: {} not in UTC by default",
+                key
+            );
+            assert!(
+                shanghai[key].as_str().unwrap().ends_with("+08:00"),
+                "case ext_46_time_zone incorrect: {} not in Asia/Shanghai",
+                key
+            );
+            assert_eq!(time(&shanghai, key), time(&utc, key));
+        }
+
+        // the list of jobs takes the same parameter alongside the filters
+        let (_, jobs) = get("jobs?problem_id=1&tz=America/New_York");
+        let new_york = &jobs[0];
+        assert!(
+            time(new_york, "created_time").offset().local_minus_utc() < 0,
+            "case ext_46_time_zone incorrect: time not in America/New_York"
+        );
+        assert_eq!(time(new_york, "created_time"), time(&utc, "created_time"));
+
+        let (status, error) = get("jobs?tz=Mars/Olympus_Mons");
+        assert_eq!(
+            (status, error["reason"].as_str()),
+            (400, Some("ERR_INVALID_ARGUMENT")),
+            "case ext_46_time_zone incorrect: unknown time zone accepted"
+        );
+    });
+}
+
+#[test]
+fn test_ext_47_password_authorization() {
+    // in open mode users without a password set the first one themselves, root included
+    // afterwards only the user or the administrator can change it
+    TestCase::read("ext_47_password_admin").run_then(|prefix| {
+        let client = Client::new();
+        let login = |user_id: usize, password: &str| {
+            client
+                .post(format!("{}/auth/login", prefix))
+                .json(&json!({ "user_id": user_id, "password": password }))
+                .send()
+                .unwrap()
+                .json::<Value>()
+                .unwrap()["token"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        let set_password = |user_id: usize, token: Option<&str>| {
+            let request = client
+                .post(format!("{}/users/{}/password", prefix, user_id))
+                .json(&json!({ "password": "changed" }));
+            match token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            }
+            .send()
+            .unwrap()
+            .status()
+            .as_u16()
+        };
+
+        let user_token = login(1, "secret");
+        assert_eq!(
+            set_password(2, Some(&user_token)),
+            401,
+            "case ext_47_password_admin incorrect: password of another user set"
+        );
+        assert_eq!(
+            set_password(0, Some(&user_token)),
+            401,
+            "case ext_47_password_admin incorrect: password of root set by a user"
+        );
+        assert_eq!(
+            set_password(1, None),
+            401,
+            "case ext_47_password_admin incorrect: password changed without a token"
+        );
+        assert_eq!(
+            set_password(1, Some(&user_token)),
+            200,
+            "case ext_47_password_admin incorrect: password not changed by the user"
+        );
+        let admin_token = login(0, "admin");
+        assert_eq!(
+            set_password(2, Some(&admin_token)),
+            200,
+            "case ext_47_password_admin incorrect: password not set by root"
+        );
+        login(2, "changed");
+    });
+
+    // out of open mode nobody can set the first password of root without a token
+    TestCase::read("ext_48_password_closed").run();
+}
+
+#[test]
+fn test_ext_49_schema_migration() {
+    // start the server on a database made before passwords, language commands, config versions
+    // and contest limits were stored, check that the missing columns are added and the old rows kept
+    for file in ["oj.db", "oj.db-wal", "oj.db-shm"] {
+        std::fs::remove_file(file).ok();
+    }
+    rusqlite::Connection::open("oj.db")
+        .unwrap()
+        .execute_batch(
+            "CREATE TABLE jobs (
+                id                  INTEGER PRIMARY KEY,
+                created_time        TEXT NOT NULL,
+                updated_time        TEXT NOT NULL,
+                source_code         TEXT NOT NULL,
+                language            TEXT NOT NULL,
+                user_id             INTEGER,
+                problem_id          INTEGER,
+                contest_id          INTEGER,
+                state               TEXT NOT NULL,
+                result              TEXT NOT NULL,
+                score               INTEGER,
+                cases               TEXT NOT NULL
+            );
+            CREATE TABLE users (
+                id                  INTEGER PRIMARY KEY,
+                name                TEXT NOT NULL
+            );
+            CREATE TABLE contests (
+                id                      INTEGER PRIMARY KEY,
+                name                    TEXT NOT NULL,
+                from_time               TEXT NOT NULL,
+                to_time                 TEXT NOT NULL,
+                problem_ids             TEXT NOT NULL,
+                user_ids                TEXT NOT NULL,
+                submission_limit        INTEGER
+            );
+            INSERT INTO users VALUES (0, 'root'), (1, 'user1');
+            INSERT INTO contests VALUES (1, 'Contest 1', '2000-01-01T00:00:00.000Z',
+                '2030-01-01T00:00:00.000Z', '[0]', '[0,1]', 2);",
+        )
+        .unwrap();
+    TestCase::read("ext_49_migration").keep_data().run();
+}
+
+#[test]
+fn test_ext_50_contest_ranklist_cache() {
+    // load the ranklist of a contest, then submit a job outside of any contest
+    // check that the cached ranklist is dropped, as the job also counts in the contest
+    TestCase::read("ext_50_contest_ranklist_cache").run();
+}
+
+#[test]
+fn test_ext_51_generator_timeout() {
+    // an input generator which never ends is killed at the time limit of its case
+    // or, without one, once the total judge time is used up, as a system error of its case only
+    TestCase::read("ext_51_generator_timeout").run();
+}
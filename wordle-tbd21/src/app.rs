@@ -0,0 +1,870 @@
+use super::{game::*, stats::*, util::*, *};
+use chrono::Utc;
+use colored::Colorize;
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::time::Duration;
+
+///Loads the statistics of the previous games for non-GUI mode
+///Games imported from CSV take the place of the state file, while those imported from NYT are added to it
+pub fn load_stats(config: &Config) -> Stats {
+    let mut stats = match config.import_csv {
+        Some(ref filename) => match fs::read_to_string(filename)
+            .map_err(|e| e.into())
+            .and_then(|csv| Stats::import_csv(&csv))
+        {
+            Ok(stats) => stats,
+            Err(e) => {
+                invalid_arguments(
+                    config.is_tty,
+                    &format!("failed to import '{}': {}", filename, e),
+                );
+                Stats::new()
+            }
+        },
+        None => match config.state {
+            Some(ref filename) => match File::open(filename) {
+                Ok(mut file) => {
+                    let mut json = String::new();
+                    match file.read_to_string(&mut json) {
+                        Ok(_) => match Stats::from_json(&json) {
+                            Ok(stats) => stats,
+                            //Starts over instead of crashing on a corrupted state file
+                            Err(e) => {
+                                warning(
+                                    config.is_tty,
+                                    &format!("ignoring invalid state file '{}': {}", filename, e),
+                                );
+                                Stats::new()
+                            }
+                        },
+                        Err(_) => Stats::new(),
+                    }
+                }
+                Err(_) => Stats::new(),
+            },
+            None => Stats::new(),
+        },
+    };
+
+    //Merges the games imported from NYT, and saves them at once as they are not played in this session
+    if let Some(ref filename) = config.import_nyt {
+        match fs::read_to_string(filename)
+            .map_err(|e| e.into())
+            .and_then(|json| Stats::import_from_nyt(&json))
+        {
+            Ok(imported) => stats.merge(imported),
+            Err(e) => invalid_arguments(
+                config.is_tty,
+                &format!("failed to import '{}': {}", filename, e),
+            ),
+        }
+        if let Some(ref state) = config.state {
+            save_stats(state, &stats).unwrap_or_else(|_| panic!("{}", "IO failure".red().bold()));
+        }
+    }
+
+    stats
+}
+
+///Saves the statistics to the state file
+///A state file which fails to parse is moved to `<state>.bak` first, so that the history it may still hold is not lost
+pub fn save_stats(filename: &str, stats: &Stats) -> std::io::Result<()> {
+    if let Ok(json) = fs::read_to_string(filename) {
+        if !json.trim().is_empty() && Stats::from_json(&json).is_err() {
+            fs::rename(filename, format!("{}.bak", filename))?;
+        }
+    }
+    fs::write(filename, stats.to_json())
+}
+
+///Prints the statistics of the games which had the given answer
+///In non-TTY mode: the word, the times answered, the fewest and the most attempts and the win rate, all 0 if never answered
+pub fn print_word_stats(stats: &Stats, word: &str, is_tty: bool) {
+    let word = word.to_ascii_uppercase();
+    let word_stats = stats.per_word_stats().remove(&word);
+    if is_tty {
+        match word_stats {
+            Some(s) => println!(
+                "{} was the answer {} time{}, you won {}% of them in {} to {} attempts",
+                word.bold(),
+                s.times_answered.to_string().bold(),
+                make_plural(s.times_answered as i32),
+                format!("{:.0}", s.win_rate * 100.0).green().bold(),
+                s.min_attempts.to_string().bold(),
+                s.max_attempts.to_string().bold()
+            ),
+            None => println!("{} has never been the answer", word.bold()),
+        }
+        if let Some(game) = stats.best_game_for_word(&word) {
+            print_best_game(game);
+        }
+    } else {
+        let s = word_stats.unwrap_or_default();
+        println!(
+            "{} {} {} {} {:.2}",
+            word, s.times_answered, s.min_attempts, s.max_attempts, s.win_rate
+        );
+    }
+}
+
+///Prints the answer of a game won in the fewest attempts and its number of attempts, for TTY mode
+fn print_best_game(game: &Game) {
+    println!(
+        "Best game: {} in {} attempt{}",
+        game.answer.bold(),
+        game.guesses.len().to_string().bold(),
+        make_plural(game.guesses.len() as i32)
+    );
+}
+
+///Prints how often each letter is found in each position of the previous games, as a table of 26 rows
+///In non-TTY mode each row is the letter followed by its counts
+pub fn print_letter_heatmap(stats: &Stats, is_tty: bool) {
+    let frequencies = stats.aggregate_letter_frequencies();
+    if is_tty {
+        println!(
+            "{}",
+            format!(
+                "   {}",
+                (1..=DEFAULT_WORD_LENGTH)
+                    .map(|j| format!("{:>5}", j))
+                    .collect::<String>()
+            )
+            .bold()
+        );
+        //The most frequent letter of each position is highlighted
+        let most = (0..DEFAULT_WORD_LENGTH)
+            .map(|j| {
+                frequencies
+                    .values()
+                    .map(|counts| counts[j])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        for (letter, counts) in &frequencies {
+            let mut row = format!("{}  ", letter.to_string().bold());
+            for (j, n) in counts.iter().enumerate() {
+                let cell = format!("{:>5}", n);
+                if *n > 0 && *n == most[j] {
+                    row += &cell.green().bold().to_string();
+                } else {
+                    row += &cell;
+                }
+            }
+            println!("{}", row);
+        }
+    } else {
+        for (letter, counts) in &frequencies {
+            println!(
+                "{} {}",
+                letter,
+                counts
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+        }
+    }
+}
+
+///Reads the settings of the GUI configuration window saved in a state file
+///The GUI saves them to the game data storage file chosen in the window, which the state file names,
+///so they are read from there if it is another file
+///Returns: AppSettings -- the settings, the defaults if there are none
+fn load_settings(state: Option<&str>) -> AppSettings {
+    let read = |filename: &str| {
+        fs::read_to_string(filename)
+            .ok()
+            .and_then(|json| Stats::from_json(&json).ok())
+            .and_then(|stats| stats.settings)
+    };
+    let settings = state.and_then(read).unwrap_or_default();
+    match settings.stats_filename.as_str() {
+        "" => settings,
+        filename if Some(filename) == state => settings,
+        filename => read(filename).unwrap_or(settings),
+    }
+}
+
+///The main struct of the Wordle game application
+pub struct Wordle {
+    pub current_game: Game,
+
+    pub stats: Stats,
+
+    pub current_guess: String,
+
+    pub finals: Vec<String>,
+
+    pub acceptables: BTreeSet<String>,
+
+    #[cfg(feature = "gui")]
+    pub game_state: GameState,
+
+    pub config: Config,
+
+    pub day: usize,
+
+    #[cfg(feature = "gui")]
+    pub stats_filename: String,
+
+    ///Whether the input area should take the focus again, e.g. after a guess is submitted
+    #[cfg(feature = "gui")]
+    pub focus_guess_input: bool,
+
+    #[cfg(feature = "gui")]
+    pub animation_state: gui::AnimationState,
+
+    ///Notifications of the achievements earned lately, shown until they expire
+    #[cfg(feature = "gui")]
+    pub toasts: Vec<gui::Toast>,
+}
+
+impl Wordle {
+    ///Makes a new Wordle game application from the given configuration
+    pub fn new(mut finals: Vec<String>, acceptables: BTreeSet<String>, mut config: Config) -> Self {
+        if config.gui {
+            //Initialization in GUI mode
+            //The configuration window starts with the settings saved in the state file, if any,
+            //the arguments given taking priority
+            let settings = load_settings(config.state.as_deref());
+            config.difficult |= settings.difficult;
+            config.colorblind_mode |= settings.high_contrast;
+            config.max_attempts = config.max_attempts.or(settings.max_attempts);
+            config.word_length = config.word_length.or(settings.word_length);
+            #[cfg(feature = "gui")]
+            let stats_filename = match settings.stats_filename.is_empty() {
+                true => config.state.clone().unwrap_or_default(),
+                false => settings.stats_filename,
+            };
+
+            Self {
+                //Only fills the grid behind the configuration window, the first game is made when it is closed
+                current_game: Game::new_adversarial(config.word_length(), config.max_attempts()),
+
+                stats: Stats::new(),
+
+                current_guess: String::new(),
+
+                finals,
+
+                acceptables,
+
+                #[cfg(feature = "gui")]
+                game_state: GameState::Uninitialized,
+
+                config,
+
+                day: 0,
+
+                #[cfg(feature = "gui")]
+                stats_filename,
+
+                #[cfg(feature = "gui")]
+                focus_guess_input: false,
+
+                #[cfg(feature = "gui")]
+                animation_state: gui::AnimationState::default(),
+
+                #[cfg(feature = "gui")]
+                toasts: vec![],
+            }
+        } else {
+            //Initialization in non-GUI mode
+            let mut stats = load_stats(&config);
+
+            //Clears the state file before playing, so the history is gone even if no game is finished
+            //The player is asked to confirm in TTY mode, scripts are trusted to mean it
+            let reset_confirmed = config.reset_stats
+                && (!config.is_tty || {
+                    println!("This will delete your game history. Are you sure? [Y/N]");
+                    want_to_continue(None)
+                });
+            if reset_confirmed {
+                stats.reset();
+                if let Some(ref filename) = config.state {
+                    save_stats(filename, &stats)
+                        .unwrap_or_else(|_| panic!("{}", "IO failure".red().bold()));
+                }
+            }
+
+            let day = match config.day {
+                Some(d) => d,
+                None => 1,
+            };
+
+            if config.random {
+                //The arguments should not conflict with each other
+                if day == 0 {
+                    invalid_arguments(config.is_tty, "`--day` must be at least 1");
+                }
+                if day > finals.len() {
+                    invalid_arguments(
+                        config.is_tty,
+                        &format!("`--day {}` exceeds final word count {}", day, finals.len()),
+                    );
+                }
+
+                match config.word {
+                    Some(_) => {
+                        invalid_arguments(config.is_tty, "`--word` conflicts with `--random`");
+                    }
+                    None => (),
+                }
+
+                finals.shuffle(&mut rand::rngs::StdRng::seed_from_u64(match config.seed {
+                    Some(s) => s,
+                    None => 0,
+                }));
+            }
+
+            //The game given by the code is played on from its last guess
+            let game = match config.resume {
+                Some(ref code) => {
+                    match Game::decode_state(code, &finals, &acceptables, config.max_attempts()) {
+                        Ok(game) => game,
+                        Err(e) => {
+                            invalid_arguments(
+                                config.is_tty,
+                                &format!("invalid resume code: {}", e),
+                            );
+                            unreachable!()
+                        }
+                    }
+                }
+                None if config.adversarial => {
+                    Game::new_adversarial(config.word_length(), config.max_attempts())
+                }
+                None => Game::new(
+                    &pick_word(&mut config, &finals, day, None),
+                    config.max_attempts(),
+                ),
+            };
+
+            Self {
+                //Stamped with the start time until Stats::record stamps the end time
+                current_game: Game {
+                    played_at: Some(Utc::now()),
+                    ..game
+                },
+
+                stats,
+
+                current_guess: String::new(),
+
+                finals,
+
+                acceptables,
+
+                #[cfg(feature = "gui")]
+                game_state: GameState::Continue,
+
+                config,
+
+                day,
+
+                #[cfg(feature = "gui")]
+                stats_filename: String::new(),
+
+                #[cfg(feature = "gui")]
+                focus_guess_input: false,
+
+                #[cfg(feature = "gui")]
+                animation_state: gui::AnimationState::default(),
+
+                #[cfg(feature = "gui")]
+                toasts: vec![],
+            }
+        }
+    }
+
+    ///Gets the sizes of the word lists, only counting the words of the configured length
+    ///Returns: (usize, usize) -- the number of final words and the number of acceptable words
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn word_list_sizes(&self) -> (usize, usize) {
+        let count = |words: &mut dyn Iterator<Item = &String>| {
+            words
+                .filter(|word| word.len() == self.config.word_length())
+                .count()
+        };
+        (
+            count(&mut self.finals.iter()),
+            count(&mut self.acceptables.iter()),
+        )
+    }
+
+    ///Gets the lengths of the final words which can be played, in increasing order
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn word_lengths(&self) -> Vec<usize> {
+        self.finals
+            .iter()
+            .map(String::len)
+            .filter(|length| (3..=10).contains(length))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    ///Runs the Wordle game application
+    pub fn run(self) {
+        if self.config.gui {
+            #[cfg(feature = "gui")]
+            self.run_gui();
+
+            #[cfg(not(feature = "gui"))]
+            invalid_arguments(self.config.is_tty, "GUI support is not compiled in");
+        } else {
+            self.run_no_gui();
+        }
+    }
+
+    ///Runs the Wordle game application in non-GUI mode
+    fn run_no_gui(mut self) {
+        //Results of the games in this session, not counting the loaded ones
+        let mut session_wins = 0;
+        let mut session_losses = 0;
+
+        if self.config.is_tty && self.config.practice {
+            println!("{}", "(Practice mode — not recorded)".bright_blue().bold());
+        }
+
+        //Guesses can only time out if the input is read in the background
+        let timed_input = self
+            .config
+            .guess_timeout
+            .map(|_| TimedInput::spawn(io::BufReader::new(io::stdin())));
+
+        //The outer loop -- loop of games
+        'outer: loop {
+            let mut cguesses_status = vec![];
+            if self.config.hint {
+                self.print_hint();
+            }
+
+            //The inner loop -- loop of guesses
+            'inner: loop {
+                //Reads input and processes the user's guess
+                if self.config.is_tty {
+                    println!(
+                        "Attempt {}:",
+                        (self.current_game.guesses.len() + 1).to_string().bold()
+                    );
+                }
+                let guess = match (&timed_input, self.config.guess_timeout) {
+                    (Some(input), Some(seconds)) => {
+                        input.read_line_timeout(Duration::from_secs(seconds), self.config.is_tty)
+                    }
+                    _ => Some(read_input_line(None)),
+                };
+
+                let timed_out = guess.is_none();
+                let state = match guess {
+                    Some(guess) => {
+                        self.current_guess = guess.trim().to_string().to_ascii_uppercase();
+                        self.current_game.accept_guess(
+                            &self.current_guess,
+                            &self.acceptables,
+                            self.config.difficult,
+                        )
+                    }
+                    //The attempt is lost as if the guess were invalid, without a row to show
+                    None => {
+                        if self.config.is_tty {
+                            println!("{}", "Time is up".red().bold());
+                        } else {
+                            println!("INVALID");
+                        }
+                        match self.current_game.forfeit_attempt(&self.acceptables) {
+                            GameState::Lost => GameState::Lost,
+                            _ => continue 'inner,
+                        }
+                    }
+                };
+
+                //Handles invalid input
+                match state {
+                    GameState::InvalidInput => {
+                        invalid_input(self.config.is_tty);
+                        continue 'inner;
+                    }
+                    _ => (),
+                }
+
+                //A forfeited attempt has no result of its own
+                if !timed_out {
+                    self.print_guess_result(&mut cguesses_status);
+                }
+
+                if self.config.verbose {
+                    self.print_verbose(&state);
+                }
+
+                //Aftermath
+                match state {
+                    GameState::Won => {
+                        //Prints result
+                        if self.config.is_tty {
+                            println!(
+                                "{}: you attempted {} time{} in total",
+                                "Correct".green().bold(),
+                                self.current_game.guesses.len().to_string().green().bold(),
+                                make_plural(self.current_game.guesses.len() as i32)
+                            );
+                        } else {
+                            println!("CORRECT {}", self.current_game.guesses.len());
+                        }
+                        session_wins += 1;
+
+                        //Prints the emoji grid for sharing, the same in both modes
+                        if self.config.share {
+                            println!("{}", self.current_game.share_text(self.day));
+                        }
+
+                        //Records game data, except for practice
+                        if !self.config.practice {
+                            self.stats.record(self.current_game.clone());
+                        }
+                        if self.config.stats {
+                            self.print_stats();
+                        }
+
+                        //Asks if the user wants to play one more time
+                        if self.config.is_tty {
+                            println!("Do you want to play once more? [Y/N]");
+                        }
+                        if want_to_continue(timed_input.as_ref()) {
+                            self.current_game = self.replay_game(timed_input.as_ref());
+                            break 'inner;
+                        } else {
+                            break 'outer;
+                        }
+                    }
+                    GameState::Lost => {
+                        //Prints result
+                        if self.config.is_tty {
+                            println!(
+                                "{}: the answer is {}",
+                                "Failed".red().bold(),
+                                self.current_game.answer.bright_yellow().bold()
+                            );
+                        } else {
+                            println!("FAILED {}", self.current_game.answer);
+                        }
+                        session_losses += 1;
+
+                        //Prints the emoji grid for sharing, the same in both modes
+                        if self.config.share {
+                            println!("{}", self.current_game.share_text(self.day));
+                        }
+
+                        //Records game data, except for practice
+                        if !self.config.practice {
+                            self.stats.record(self.current_game.clone());
+                        }
+                        if self.config.stats {
+                            self.print_stats();
+                        }
+
+                        //Asks if the user wants to play one more time
+                        if self.config.is_tty {
+                            println!("Do you want to play once more? [Y/N]");
+                        }
+                        if want_to_continue(timed_input.as_ref()) {
+                            self.current_game = self.replay_game(timed_input.as_ref());
+                            break 'inner;
+                        } else {
+                            break 'outer;
+                        }
+                    }
+                    _ => {
+                        if self.config.hint {
+                            self.print_hint();
+                        }
+                        continue 'inner;
+                    }
+                }
+            }
+        }
+
+        //Marks the end of the session for scripts driving the game
+        if !self.config.is_tty {
+            println!(
+                "SESSION {} {} {}",
+                session_wins + session_losses,
+                session_wins,
+                session_losses
+            );
+        }
+
+        //Save the statistics to the given JSON file, which practice leaves untouched
+        match self.config.state {
+            Some(ref filename) if !self.config.practice => {
+                save_stats(filename, &self.stats).expect(&format!("{}", "IO failure".red().bold()))
+            }
+            _ => (),
+        }
+
+        //Exports the games to the given CSV file
+        if let Some(ref filename) = self.config.export_csv {
+            fs::write(filename, self.stats.export_csv())
+                .unwrap_or_else(|_| panic!("{}", "IO failure".red().bold()));
+        }
+    }
+
+    ///Makes the game played after "play once more" in non-GUI mode
+    ///Practice replays the same answer without moving on to the next day, so a word can be drilled
+    fn replay_game(&mut self, timed_input: Option<&TimedInput>) -> Game {
+        match self.config.practice {
+            true if self.current_game.adversarial => {
+                Game::new_adversarial(self.config.word_length(), self.config.max_attempts())
+            }
+            true => Game::new(&self.current_game.answer, self.config.max_attempts()),
+            false => {
+                self.day += 1;
+                self.next_game(timed_input)
+            }
+        }
+    }
+
+    ///Makes the game of the next day in non-GUI mode, without picking an answer in adversarial mode
+    fn next_game(&mut self, timed_input: Option<&TimedInput>) -> Game {
+        match self.config.adversarial {
+            true => Game::new_adversarial(self.config.word_length(), self.config.max_attempts()),
+            false => Game::new(
+                &pick_word(&mut self.config, &self.finals, self.day, timed_input),
+                self.config.max_attempts(),
+            ),
+        }
+    }
+
+    ///Prints the 3 guesses expected to give the most information about the possible answers
+    fn print_hint(&self) {
+        let candidates = self
+            .current_game
+            .possible_words(&self.finals.iter().cloned().collect());
+        let hints = self
+            .current_game
+            .ranked_guesses(&candidates, &self.acceptables)
+            .into_iter()
+            .take(3)
+            .map(|(word, _)| word)
+            .collect::<Vec<_>>();
+        if self.config.is_tty {
+            println!("{}: {}", "Hint".bright_blue().bold(), hints.join(" "));
+        } else {
+            println!("HINT {}", hints.join(" "));
+        }
+    }
+
+    ///Prints the details after a guess in verbose mode, the same in both modes
+    ///The lines start with '#' so that scripts reading the non-TTY output can skip them
+    fn print_verbose(&self, state: &GameState) {
+        //The answer of an adversarial game can be any acceptable word
+        let candidates = match self.current_game.adversarial {
+            true => self.current_game.possible_words(&self.acceptables),
+            false => self
+                .current_game
+                .possible_words(&self.finals.iter().cloned().collect()),
+        };
+        println!("# Remaining candidates: {}", candidates.len());
+        match state {
+            GameState::Won | GameState::Lost => {
+                println!("# Answer: {}", self.current_game.answer);
+            }
+            _ => {
+                if let Some(guess) = self
+                    .current_game
+                    .best_next_guess(&candidates, &self.acceptables)
+                {
+                    println!("# Suggested: {}", guess);
+                }
+                //The game can be played on from here with `--resume`
+                if let Ok(code) = self
+                    .current_game
+                    .encode_state(&self.finals, &self.acceptables)
+                {
+                    println!("# Resume code: {}", code);
+                }
+            }
+        }
+    }
+
+    ///Prints the status of the last guess and of the letters, and the candidates left if asked to
+    ///In TTY mode the guesses of the game so far are all printed again, colorized in cguesses_status
+    fn print_guess_result(&self, cguesses_status: &mut Vec<String>) {
+        if self.config.is_tty {
+            let colorize = if self.config.colorblind_mode {
+                colorize_tty_colorblind
+            } else {
+                colorize_tty
+            };
+            println!("Results:");
+            let mut cguess_status = String::new();
+            let mut cletters_status = String::new();
+            for (i, letter) in self.current_game.guesses_status[self.current_game.guesses.len() - 1]
+                .iter()
+                .enumerate()
+            {
+                cguess_status += &colorize(*letter, self.current_guess.chars().nth(i).unwrap());
+            }
+            cguesses_status.push(cguess_status);
+            for (i, letter) in self
+                .current_game
+                .letters_status
+                .values()
+                .into_iter()
+                .enumerate()
+            {
+                cletters_status += &colorize(*letter, LETTERS[i]);
+            }
+            for attempt in cguesses_status.iter() {
+                println!("{}", attempt);
+            }
+            println!("{}", cletters_status);
+        } else {
+            println!(
+                "{} {}",
+                self.current_game.guesses_status[self.current_game.guesses_status.len() - 1]
+                    .iter()
+                    .collect::<String>(),
+                self.current_game
+                    .letters_status
+                    .values()
+                    .into_iter()
+                    .collect::<String>()
+            );
+        }
+
+        //Prints the number of acceptable words consistent with the guesses so far
+        if self.config.show_candidates {
+            let candidates = self.current_game.possible_words(&self.acceptables).len();
+            if self.config.is_tty {
+                println!(
+                    "{} possible word{} left",
+                    candidates.to_string().bold(),
+                    make_plural(candidates as i32)
+                );
+            } else {
+                println!("CANDIDATES {}", candidates);
+            }
+        }
+    }
+
+    ///Prints game statistics
+    fn print_stats(&self) {
+        if self.config.is_tty {
+            println!("{}", "Statistics:".bold());
+            println!(
+                "You have won {} time{}",
+                self.stats.success.to_string().green().bold(),
+                make_plural(self.stats.success)
+            );
+            println!(
+                "You have lost {} time{}",
+                self.stats.failure.to_string().red().bold(),
+                make_plural(self.stats.failure)
+            );
+            println!(
+                "You attempted {} time{} in average to win a game",
+                format!("{:.2}", self.stats.average_attempts())
+                    .bright_yellow()
+                    .bold(),
+                make_plural(self.stats.average_attempts().floor() as i32)
+            );
+            println!(
+                "Your current win streak is {}, the best is {}",
+                self.stats.current_streak.to_string().green().bold(),
+                self.stats.best_streak.to_string().green().bold()
+            );
+            if let Some(game) = self.stats.best_game() {
+                print_best_game(game);
+            }
+            let earned = self
+                .stats
+                .achievements
+                .iter()
+                .filter(|achievement| achievement.earned_at.is_some())
+                .collect::<Vec<_>>();
+            if !earned.is_empty() {
+                println!("Achievements:");
+                for achievement in earned {
+                    println!(
+                        "{}    {}",
+                        achievement.name.bright_yellow().bold(),
+                        achievement.description
+                    );
+                }
+            }
+            //The longest bar takes 20 blocks, any won count takes at least one
+            println!("Guess distribution:");
+            let distribution = self.stats.guess_distribution();
+            let most = distribution.iter().copied().max().unwrap_or(0).max(1);
+            for (i, count) in distribution.iter().enumerate() {
+                println!(
+                    "{} | {} ({})",
+                    i + 1,
+                    "█".repeat((count * 20).div_ceil(most)).green(),
+                    count
+                );
+            }
+            println!("The top 5 words you tried most frequently are:");
+            for (word, n) in self.stats.most_frequent() {
+                println!(
+                    "{}    {} time{}",
+                    word.bold(),
+                    n.to_string().bold(),
+                    make_plural(*n)
+                );
+            }
+            println!("The letters you met most often in place are:");
+            for (letter, position, n) in self.stats.top_letter_positions() {
+                println!(
+                    "{} in position {}    {} time{}",
+                    letter.to_string().bold(),
+                    position,
+                    n.to_string().bold(),
+                    make_plural(n as i32)
+                );
+            }
+            if self.config.verbose {
+                println!("The 5 answers you struggled with most are:");
+                for (word, s) in self.stats.hardest_answers(5) {
+                    println!(
+                        "{}    won {}% of {} game{}",
+                        word.bold(),
+                        format!("{:.0}", s.win_rate * 100.0).bold(),
+                        s.times_answered.to_string().bold(),
+                        make_plural(s.times_answered as i32)
+                    );
+                }
+            }
+        } else {
+            println!(
+                "{} {} {:.2}",
+                self.stats.success,
+                self.stats.failure,
+                self.stats.average_attempts()
+            );
+            let mut output = String::new();
+            for (word, n) in self.stats.most_frequent() {
+                output += &format!("{} {} ", word, n);
+            }
+            println!("{}", output.trim_end());
+            if self.config.verbose {
+                let mut output = String::new();
+                for (word, s) in self.stats.hardest_answers(5) {
+                    output += &format!("{} {:.2} ", word, s.win_rate);
+                }
+                println!("{}", output.trim_end());
+                let mut output = String::new();
+                for (letter, position, n) in self.stats.top_letter_positions() {
+                    output += &format!("{}{} {} ", letter, position, n);
+                }
+                println!("{}", output.trim_end());
+            }
+        }
+    }
+}
@@ -1,9 +1,19 @@
-use super::{stats::*, util::*, *};
-use colored::Colorize;
-use eframe::egui::{self, vec2};
+use super::*;
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::BTreeSet;
-use std::fs::{self, File};
+use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+///Ranking of the first guesses, which only depends on the word lists, so it is kept for the lists it was computed with
+///The lists are identified by a hash of the word length, the candidates and all the words
+static FIRST_GUESS_RANKING: Mutex<Option<(u64, Ranking)>> = Mutex::new(None);
+
+///Words ranked as the next guess with the expected information they give, best first
+type Ranking = Vec<(String, f32)>;
 
 ///Game state indicator
 pub enum GameState {
@@ -11,6 +21,8 @@ pub enum GameState {
     Won,
     Lost,
     InvalidInput,
+
+    #[cfg(feature = "gui")]
     Uninitialized,
 }
 
@@ -21,778 +33,697 @@ pub struct Game {
 
     pub guesses: Vec<String>,
 
+    ///Only stored in the state file if it is not the default
+    #[serde(
+        default = "default_word_length",
+        skip_serializing_if = "is_default_word_length"
+    )]
+    pub word_length: usize,
+
+    ///Only stored in the state file if it is not the default
+    #[serde(
+        default = "default_max_attempts",
+        skip_serializing_if = "is_default_max_attempts"
+    )]
+    pub max_attempts: usize,
+
+    ///When the game was played, missing in the state files saved before it was recorded
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "date_time_format"
+    )]
+    pub played_at: Option<DateTime<Utc>>,
+
+    ///Whether the answer is chosen against each guess instead of being fixed, see accept_guess
+    #[serde(skip, default)]
+    pub adversarial: bool,
+
+    ///Attempts given up without a guess, see forfeit_attempt
+    #[serde(skip, default)]
+    pub forfeited: usize,
+
     #[serde(skip, default)]
     pub guesses_status: Vec<Vec<char>>,
 
     #[serde(skip, default)]
     pub letters_status: BTreeMap<char, char>,
+}
 
-    #[serde(skip, default)]
-    pub answer_count: BTreeMap<char, usize>,
+fn default_word_length() -> usize {
+    DEFAULT_WORD_LENGTH
 }
 
-impl Game {
-    ///Makes a new Game
-    pub fn new(answer: &str) -> Self {
-        let answer = answer.to_ascii_uppercase();
-        let mut answer_count = BTreeMap::new();
-        for letter in answer.chars() {
-            answer_count.insert(letter, answer.chars().filter(|c| *c == letter).count());
-        }
-        Self {
-            answer,
+fn is_default_word_length(word_length: &usize) -> bool {
+    *word_length == DEFAULT_WORD_LENGTH
+}
 
-            guesses: Vec::new(),
+fn default_max_attempts() -> usize {
+    DEFAULT_MAX_ATTEMPTS
+}
 
-            guesses_status: Vec::new(),
+fn is_default_max_attempts(max_attempts: &usize) -> bool {
+    *max_attempts == DEFAULT_MAX_ATTEMPTS
+}
 
-            letters_status: LETTERS.iter().map(|c| (*c, 'X')).collect(),
+///Serialization of optional timestamps, e.g. "2022-07-01T08:00:00.000Z"
+pub mod date_time_format {
+    use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    pub const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
 
-            answer_count,
+    ///Parses a timestamp, any RFC 3339 timestamp is accepted and normalized to UTC
+    pub fn parse(s: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+        match DateTime::parse_from_rfc3339(s) {
+            Ok(date) => Ok(date.with_timezone(&Utc)),
+            Err(_) => {
+                NaiveDateTime::parse_from_str(s, FORMAT).map(|date| Utc.from_utc_datetime(&date))
+            }
         }
     }
 
-    ///Accepts and processes a new guess
-    pub fn accept_guess(
-        &mut self,
-        guess: &str,
-        acceptables: &BTreeSet<String>,
-        is_difficult: bool,
-    ) -> GameState {
-        //Preprocess
-        let guess = guess.to_ascii_uppercase();
-        let mut guess_status = vec!['X'; 5];
-        let mut counter = self.answer_count.clone();
-
-        //Guess validation
-        if !acceptables.contains(&guess) {
-            return GameState::InvalidInput;
+    pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match date {
+            Some(date) => serializer.serialize_str(&date.format(FORMAT).to_string()),
+            None => serializer.serialize_none(),
         }
+    }
 
-        //Judges whether the player's guess is valid when in difficult mode
-        if is_difficult {
-            for letter in self.answer.chars() {
-                if (*self.letters_status.get(&letter).unwrap() == 'G'
-                    && guess
-                        .chars()
-                        .nth(self.answer.chars().position(|c| c == letter).unwrap())
-                        .unwrap()
-                        != letter)
-                    || (*self.letters_status.get(&letter).unwrap() == 'Y'
-                        && !guess.contains(letter))
-                {
-                    return GameState::InvalidInput;
-                }
-            }
+    pub fn deserialize<'d, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'d>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) => parse(&s).map(Some).map_err(serde::de::Error::custom),
+            None => Ok(None),
         }
+    }
+}
 
-        //Marks all correct letters
-        for (j, letter) in guess.chars().enumerate() {
-            if self.answer.chars().nth(j).unwrap() == letter {
-                self.letters_status.insert(letter, 'G');
-                counter.entry(letter).and_modify(|c| {
-                    *c -= 1;
-                });
-                guess_status[j] = 'G';
-            }
+///Gets the status of each letter in a guess against an answer of the same length
+///Arguments: answer: &str -- the answer, guess: &str -- the guess, both in upper case
+///Returns: Vec<char> -- 'G' for letters in place, 'Y' for letters elsewhere in the answer, 'R' for the others
+///A repeated letter is marked 'Y' only as many times as it is left in the answer after the 'G' ones
+pub fn guess_status(answer: &str, guess: &str) -> Vec<char> {
+    let mut guess_status = vec!['X'; answer.chars().count()];
+    let mut counter = BTreeMap::new();
+    for letter in answer.chars() {
+        *counter.entry(letter).or_insert(0) += 1;
+    }
+
+    //Marks all correct letters
+    for (j, (letter, expected)) in guess.chars().zip(answer.chars()).enumerate() {
+        if letter == expected {
+            counter.entry(letter).and_modify(|c| *c -= 1);
+            guess_status[j] = 'G';
         }
+    }
 
-        //Marks the rest of the letters
-        for (j, letter) in guess.chars().enumerate() {
-            if guess_status[j] != 'G' {
-                //Only scans the letters not marked as green before
-                if self.answer.contains(letter) && *counter.get(&letter).unwrap() > 0 {
-                    //Marks as yellow
+    //Marks the rest of the letters as yellow while they are left in the answer, otherwise red
+    for (j, letter) in guess.chars().enumerate() {
+        if guess_status[j] != 'G' {
+            match counter.get_mut(&letter) {
+                Some(c) if *c > 0 => {
+                    *c -= 1;
                     guess_status[j] = 'Y';
-                    match *self.letters_status.get(&letter).unwrap() {
-                        'X' | 'R' => {
-                            self.letters_status.insert(letter, 'Y');
-                        }
-                        _ => (),
-                    }
-                    counter.entry(letter).and_modify(|c| {
-                        *c -= 1;
-                    });
-                } else {
-                    //Marks as red
-                    match *self.letters_status.get(&letter).unwrap() {
-                        'X' => {
-                            self.letters_status.insert(letter, 'R');
-                        }
-                        _ => (),
-                    }
-                    guess_status[j] = 'R';
                 }
+                _ => guess_status[j] = 'R',
             }
         }
+    }
 
-        //Stores the result
-        self.guesses.push(guess);
-        self.guesses_status.push(guess_status);
+    guess_status
+}
 
-        //Decides the game state
-        if self.guesses[self.guesses.len() - 1] == self.answer {
-            GameState::Won
-        } else if self.guesses.len() == 6 {
-            GameState::Lost
+///Encodes the status of a guess as a number in base 3, following the same rules as guess_status
+///Used by the solver, which compares a lot of statuses
+fn status_code(answer: &[u8], guess: &[u8]) -> usize {
+    let mut counter = [0u8; 26];
+    let mut status = [0usize; 10];
+
+    //2 for green, 1 for yellow, 0 for red
+    for (j, (letter, expected)) in guess.iter().zip(answer).enumerate() {
+        if letter == expected {
+            status[j] = 2;
         } else {
-            GameState::Continue
+            counter[(expected.to_ascii_uppercase() - b'A') as usize] += 1;
+        }
+    }
+    for (j, letter) in guess.iter().enumerate() {
+        let c = &mut counter[(letter.to_ascii_uppercase() - b'A') as usize];
+        if status[j] != 2 && *c > 0 {
+            *c -= 1;
+            status[j] = 1;
         }
     }
 
+    status[..guess.len()]
+        .iter()
+        .fold(0, |code, status| code * 3 + status)
 }
 
-///The main struct of the Wordle game application
-pub struct Wordle {
-    current_game: Game,
-
-    stats: Stats,
-
-    current_guess: String,
-
-    finals: Vec<String>,
-
-    acceptables: BTreeSet<String>,
-
-    game_state: GameState,
-
-    config: Config,
-
-    day: usize,
+///Computes the Shannon entropy of the partition of the candidates by the status a guess gets
+///Arguments: guess: &str -- the guess, candidates: &[String] -- the possible answers, counts: &mut [usize] -- zeroed buffer of 3^length counters
+///Returns: f32 -- the expected information given by the guess in bits
+fn entropy(guess: &str, candidates: &[String], counts: &mut [usize]) -> f32 {
+    let mut codes = Vec::new();
+    for candidate in candidates {
+        let code = status_code(candidate.as_bytes(), guess.as_bytes());
+        if counts[code] == 0 {
+            codes.push(code);
+        }
+        counts[code] += 1;
+    }
 
-    stats_filename: String,
+    let total = candidates.len() as f32;
+    let mut entropy = 0.0;
+    for code in codes {
+        let p = counts[code] as f32 / total;
+        entropy -= p * p.log2();
+        counts[code] = 0;
+    }
+    entropy
 }
 
-impl Wordle {
-    ///Makes a new Wordle game application from the given configuration
-    pub fn new(mut finals: Vec<String>, acceptables: BTreeSet<String>, mut config: Config) -> Self {
-        if config.gui {
-            //Initialization in GUI mode
-            Self {
-                current_game: Game::new(random_pick(&finals)),
-
-                stats: Stats::new(),
+///Checks a guess, complete or not, against the rules of difficult mode
+///Like the official hard mode, the guess must respect the feedback of the previous guess:
+///green letters must stay in place, and yellow letters must be reused somewhere,
+///as many times as they were revealed in the previous guess
+///A partial guess breaks the rules once a typed letter takes the place of a green one,
+///or the letters still required no longer fit in the positions left
+///Arguments: guess: &str -- the letters typed so far in uppercase, word_length: usize -- the length of a complete guess,
+///guesses: &[String] -- the previous guesses, guesses_status: &[Vec<char>] -- the status of the previous guesses
+pub fn breaks_difficult_mode(
+    guess: &str,
+    word_length: usize,
+    guesses: &[String],
+    guesses_status: &[Vec<char>],
+) -> bool {
+    let (Some(previous), Some(status)) = (guesses.last(), guesses_status.last()) else {
+        return false;
+    };
+    let typed = guess.chars().collect::<Vec<_>>();
+    let mut required: BTreeMap<char, usize> = BTreeMap::new();
+    for (j, (letter, letter_status)) in previous.chars().zip(status).enumerate() {
+        if *letter_status == 'G' && typed.get(j).is_some_and(|c| *c != letter) {
+            return true;
+        }
+        if *letter_status == 'G' || *letter_status == 'Y' {
+            *required.entry(letter).or_insert(0) += 1;
+        }
+    }
+    let missing: usize = required
+        .into_iter()
+        .map(|(letter, count)| count.saturating_sub(typed.iter().filter(|c| **c == letter).count()))
+        .sum();
+    missing > word_length.saturating_sub(typed.len())
+}
 
-                current_guess: String::new(),
+///Appends a letter to a guess being typed in the GUI, unless the guess is complete or the keys are locked
+///Arguments: guess: &mut String -- the letters typed so far, ch: char -- the letter of the key,
+///word_length: usize -- the length of a complete guess, locked: bool -- whether the last guess is being revealed
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn push_letter(guess: &mut String, ch: char, word_length: usize, locked: bool) {
+    if !locked && guess.chars().count() < word_length {
+        guess.push(ch);
+    }
+}
 
-                finals,
+///Deletes the last letter of a guess being typed in the GUI, unless the keys are locked
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn pop_letter(guess: &mut String, locked: bool) {
+    if !locked {
+        guess.pop();
+    }
+}
 
-                acceptables,
+///Checks whether the keys deleting letters of a guess being typed in the GUI are enabled
+///They are only enabled if there is a letter to delete and the keys are not locked
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn can_delete_letters(guess: &str, locked: bool) -> bool {
+    !guess.is_empty() && !locked
+}
 
-                game_state: GameState::Uninitialized,
+///Cuts a guess typed in the input area of the GUI to the length of a complete guess
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn truncate_guess(guess: &str, word_length: usize) -> String {
+    guess.chars().take(word_length).collect()
+}
 
-                config,
+///Evaluates a guess against the answer, independent of any user interface
+///Arguments: answer: &str -- the answer, guess: &str -- the guess, acceptables: &BTreeSet<String> -- the acceptable words,
+///is_difficult: bool -- whether in difficult mode, guesses: &[String] -- the previous guesses, guesses_status: &[Vec<char>] -- the status of the previous guesses,
+///letters_status: &BTreeMap<char, char> -- the status of letters before the guess
+///Returns: Option<(Vec<char>, BTreeMap<char, char>)> -- the status of each letter in the guess and the updated status of letters, None if the guess is invalid
+pub fn evaluate_guess(
+    answer: &str,
+    guess: &str,
+    acceptables: &BTreeSet<String>,
+    is_difficult: bool,
+    guesses: &[String],
+    guesses_status: &[Vec<char>],
+    letters_status: &BTreeMap<char, char>,
+) -> Option<(Vec<char>, BTreeMap<char, char>)> {
+    //Preprocess
+    let answer = answer.to_ascii_uppercase();
+    let guess = guess.to_ascii_uppercase();
+    let mut letters_status = letters_status.clone();
+
+    //Guess validation
+    if !acceptables.contains(&guess) {
+        return None;
+    }
 
-                day: 0,
+    //Judges whether the player's guess is valid when in difficult mode
+    if is_difficult && breaks_difficult_mode(&guess, guess.chars().count(), guesses, guesses_status)
+    {
+        return None;
+    }
 
-                stats_filename: String::new(),
+    //Updates the status of letters, green over yellow over red
+    let guess_status = guess_status(&answer, &guess);
+    for (letter, status) in guess.chars().zip(&guess_status) {
+        match (status, *letters_status.get(&letter).unwrap()) {
+            ('G', _) | ('Y', 'X' | 'R') | ('R', 'X') => {
+                letters_status.insert(letter, *status);
             }
-        } else {
-            //Initialization in non-GUI mode
-            let stats = match config.state {
-                Some(ref filename) => match File::open(filename) {
-                    Ok(mut file) => {
-                        let mut json = String::new();
-                        match file.read_to_string(&mut json) {
-                            Ok(_) => Stats::from_json(&json)
-                                .expect(&format!("{}", "IO failure".red().bold())),
-                            Err(_) => Stats::new(),
-                        }
-                    }
-                    Err(_) => Stats::new(),
-                },
-                None => Stats::new(),
-            };
-
-            let day = match config.day {
-                Some(d) => d,
-                None => 1,
-            };
-
-            if config.random {
-                //The arguments should not conflict with each other
-                if day > finals.len() {
-                    invalid_arguments(config.is_tty);
-                }
+            _ => (),
+        }
+    }
 
-                match config.word {
-                    Some(_) => {
-                        invalid_arguments(config.is_tty);
-                    }
-                    None => (),
-                }
+    Some((guess_status, letters_status))
+}
 
-                finals.shuffle(&mut rand::rngs::StdRng::seed_from_u64(match config.seed {
-                    Some(s) => s,
-                    None => 0,
-                }));
-            }
+impl Game {
+    ///Makes a new Game which is lost after max_attempts wrong guesses
+    ///The length of the words is decided by the answer
+    pub fn new(answer: &str, max_attempts: usize) -> Self {
+        Self {
+            answer: answer.to_ascii_uppercase(),
 
-            Self {
-                current_game: Game::new(&pick_word(&mut config, &finals, day)),
+            guesses: Vec::new(),
 
-                stats,
+            word_length: answer.chars().count(),
 
-                current_guess: String::new(),
+            max_attempts,
 
-                finals,
+            played_at: None,
 
-                acceptables,
+            adversarial: false,
 
-                game_state: GameState::Continue,
+            forfeited: 0,
 
-                config,
+            guesses_status: Vec::new(),
 
-                day,
+            letters_status: LETTERS.iter().map(|c| (*c, 'X')).collect(),
+        }
+    }
 
-                stats_filename: String::new(),
-            }
+    ///Makes a new adversarial Game, which has no answer until the first guess
+    pub fn new_adversarial(word_length: usize, max_attempts: usize) -> Self {
+        Self {
+            word_length,
+            adversarial: true,
+            ..Self::new("", max_attempts)
         }
     }
 
-    ///Runs the Wordle game application
-    pub fn run(self) {
-        if self.config.gui {
-            self.run_gui();
+    ///Makes the emoji grid summary of a finished game, headed by the day and the number of attempts
+    ///A lost game is counted as X attempts, and each guess makes a row of the grid
+    pub fn share_text(&self, day: usize) -> String {
+        let attempts = if self.guesses.last() == Some(&self.answer) {
+            self.guesses.len().to_string()
         } else {
-            self.run_no_gui();
+            "X".to_string()
+        };
+        let mut text = format!("Wordle Day {} {}/{}", day, attempts, self.max_attempts);
+        for guess_status in &self.guesses_status {
+            text.push('\n');
+            text += &guess_status
+                .iter()
+                .map(|status| emoji_status(*status))
+                .collect::<String>();
         }
+        text
     }
 
-    ///Runs the Wordle game application in GUI mode
-    fn run_gui(self) {
-        let options = eframe::NativeOptions {
-            resizable: false,
-            initial_window_size: Some(vec2(395.0, 555.0)),
-            ..Default::default()
+    ///Encodes the answer and the guesses into a code from which the game can be resumed, see decode_state
+    ///The answer is stored as its index among the sorted final words and each guess as its index among
+    ///the acceptable words, 2 bytes each, with the number of guesses in 1 byte between them
+    ///Returns: Result<String, Box<dyn Error>> -- the URL-safe base64 of the bytes,
+    ///an error for an adversarial game, whose answer is not decided yet, or words that can't be stored
+    pub fn encode_state(
+        &self,
+        finals: &[String],
+        acceptables: &BTreeSet<String>,
+    ) -> Result<String, Box<dyn Error>> {
+        if self.adversarial {
+            return Err("an adversarial game has no answer to share".into());
+        }
+        let index = |word: &String, words: &BTreeSet<String>| {
+            words
+                .iter()
+                .position(|w| w == word)
+                .and_then(|i| u16::try_from(i).ok())
+                .map(u16::to_be_bytes)
+                .ok_or_else(|| format!("'{}' can't be stored in the code", word))
         };
 
-        eframe::run_native("Wordle", options, Box::new(|_cc| Box::new(self)));
+        let mut bytes = index(&self.answer, &finals.iter().cloned().collect())?.to_vec();
+        bytes.push(u8::try_from(self.guesses.len())?);
+        for guess in &self.guesses {
+            bytes.extend(index(guess, acceptables)?);
+        }
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(bytes))
     }
 
-    ///Runs the Wordle game application in non-GUI mode
-    fn run_no_gui(mut self) {
-        //The outer loop -- loop of games
-        'outer: loop {
-            let mut cguesses_status = vec![];
-
-            //The inner loop -- loop of guesses
-            'inner: loop {
-                //Reads input and processes the user's guess
-                if self.config.is_tty {
-                    println!(
-                        "Attempt {}:",
-                        (self.current_game.guesses.len() + 1).to_string().bold()
-                    );
-                }
-                self.current_guess = String::new();
-                io::stdin()
-                    .read_line(&mut self.current_guess)
-                    .expect(&format!("{}", "IO failure".red().bold()));
-                self.current_guess = self.current_guess.trim().to_string().to_ascii_uppercase();
-
-                let state = self.current_game.accept_guess(
-                    &self.current_guess,
-                    &self.acceptables,
-                    self.config.difficult,
-                );
-
-                //Handles invalid input
-                match state {
-                    GameState::InvalidInput => {
-                        invalid_input(self.config.is_tty);
-                        continue 'inner;
-                    }
-                    _ => (),
-                }
-
-                //Prints result
-                if self.config.is_tty {
-                    println!("Results:");
-                    let mut cguess_status = String::new();
-                    let mut cletters_status = String::new();
-                    for (i, letter) in self.current_game.guesses_status
-                        [self.current_game.guesses.len() - 1]
-                        .iter()
-                        .enumerate()
-                    {
-                        cguess_status +=
-                            &colorize_tty(*letter, self.current_guess.chars().nth(i).unwrap());
-                    }
-                    cguesses_status.push(cguess_status);
-                    for (i, letter) in self
-                        .current_game
-                        .letters_status
-                        .values()
-                        .into_iter()
-                        .enumerate()
-                    {
-                        cletters_status += &colorize_tty(*letter, LETTERS[i]);
-                    }
-                    for attempt in &cguesses_status {
-                        println!("{}", attempt);
-                    }
-                    println!("{}", cletters_status);
-
-                } else {
-                    println!(
-                        "{} {}",
-                        self.current_game.guesses_status
-                            [self.current_game.guesses_status.len() - 1]
-                            .iter()
-                            .collect::<String>(),
-                        self.current_game
-                            .letters_status
-                            .values()
-                            .into_iter()
-                            .collect::<String>()
-                    );
+    ///Makes the game encoded by encode_state, with the guesses played again against the answer
+    ///Returns: Result<Game, Box<dyn Error>> -- the game, an error if the code is malformed,
+    ///does not match the word lists or gives a game already over
+    pub fn decode_state(
+        encoded: &str,
+        finals: &[String],
+        acceptables: &BTreeSet<String>,
+        max_attempts: usize,
+    ) -> Result<Game, Box<dyn Error>> {
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(encoded.trim())?;
+        if bytes.len() < 3 || bytes.len() != 3 + 2 * bytes[2] as usize {
+            return Err("the code has a wrong length".into());
+        }
+        let index = |i: usize| u16::from_be_bytes([bytes[i], bytes[i + 1]]) as usize;
+
+        let answer = finals
+            .iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .nth(index(0))
+            .ok_or("the answer is not in the final set")?;
+        let mut game = Game::new(answer, max_attempts);
+        for i in (3..bytes.len()).step_by(2) {
+            let guess = acceptables
+                .iter()
+                .nth(index(i))
+                .ok_or("a guess is not in the acceptable set")?;
+            match game.accept_guess(guess, acceptables, false) {
+                GameState::Continue => (),
+                GameState::InvalidInput => {
+                    return Err(format!("'{}' can't be guessed", guess).into())
                 }
+                _ => return Err("the game is already over".into()),
+            }
+        }
+        Ok(game)
+    }
 
-                //Aftermath
-                match state {
-                    GameState::Won => {
-                        //Prints result
-                        if self.config.is_tty {
-                            println!(
-                                "{}: you attempted {} time{} in total",
-                                "Correct".green().bold(),
-                                self.current_game.guesses.len().to_string().green().bold(),
-                                make_plural(self.current_game.guesses.len() as i32)
-                            );
-                        } else {
-                            println!("CORRECT {}", self.current_game.guesses.len());
-                        }
-
-                        //Records game data
-                        self.stats.record(self.current_game.clone());
-                        if self.config.stats {
-                            self.print_stats();
-                        }
-
-                        //Asks if the user wants to play one more time
-                        if self.config.is_tty {
-                            println!("Do you want to play once more? [Y/N]");
-                        }
-                        if want_to_continue() {
-                            self.day += 1;
-                            self.current_game =
-                                Game::new(&pick_word(&mut self.config, &self.finals, self.day));
-                            break 'inner;
-                        } else {
-                            break 'outer;
-                        }
-                    }
-                    GameState::Lost => {
-                        //Prints result
-                        if self.config.is_tty {
-                            println!(
-                                "{}: the answer is {}",
-                                "Failed".red().bold(),
-                                self.current_game.answer.bright_yellow().bold()
-                            );
-                        } else {
-                            println!("FAILED {}", self.current_game.answer);
-                        }
-
-                        //Records game data
-                        self.stats.record(self.current_game.clone());
-                        if self.config.stats {
-                            self.print_stats();
-                        }
-
-                        //Asks if the user wants to play one more time
-                        if self.config.is_tty {
-                            println!("Do you want to play once more? [Y/N]");
-                        }
-                        if want_to_continue() {
-                            self.day += 1;
-                            self.current_game =
-                                Game::new(&pick_word(&mut self.config, &self.finals, self.day));
-                            break 'inner;
-                        } else {
-                            break 'outer;
-                        }
-                    }
-                    _ => {
-                        continue 'inner;
-                    }
+    ///Counts how often each letter appears in each of the first 5 positions of the words
+    ///Positions beyond the fifth, only found in longer words, are not counted
+    ///Returns: BTreeMap<char, [usize; 5]> -- the counts of every letter, by position
+    pub fn letter_frequencies(words: &[String]) -> BTreeMap<char, [usize; DEFAULT_WORD_LENGTH]> {
+        let mut frequencies = LETTERS
+            .iter()
+            .map(|c| (*c, [0; DEFAULT_WORD_LENGTH]))
+            .collect::<BTreeMap<_, _>>();
+        for word in words {
+            for (j, letter) in word
+                .to_ascii_uppercase()
+                .chars()
+                .take(DEFAULT_WORD_LENGTH)
+                .enumerate()
+            {
+                if let Some(counts) = frequencies.get_mut(&letter) {
+                    counts[j] += 1;
                 }
             }
         }
+        frequencies
+    }
 
-        //Save the statistics to the given JSON file
-        match self.config.state {
-            Some(ref filename) => fs::write(filename, self.stats.to_json())
-                .expect(&format!("{}", "IO failure".red().bold())),
-            None => (),
-        }
+    ///Gets the acceptable words still consistent with the status of all the guesses so far
+    ///A word is consistent if it would have given the same status to each guess as the answer did
+    pub fn possible_words(&self, acceptables: &BTreeSet<String>) -> Vec<String> {
+        acceptables
+            .iter()
+            .filter(|word| {
+                word.chars().count() == self.word_length
+                    && self
+                        .guesses
+                        .iter()
+                        .zip(&self.guesses_status)
+                        .all(|(guess, status)| guess_status(word, guess) == *status)
+            })
+            .cloned()
+            .collect()
     }
 
-    ///Prints game statistics
-    fn print_stats(&self) {
-        if self.config.is_tty {
-            println!("{}", "Statistics:".bold());
-            println!(
-                "You have won {} time{}",
-                self.stats.success.to_string().green().bold(),
-                make_plural(self.stats.success)
-            );
-            println!(
-                "You have lost {} time{}",
-                self.stats.failure.to_string().red().bold(),
-                make_plural(self.stats.failure)
-            );
-            println!(
-                "You attempted {} time{} in average to win a game",
-                format!("{:.2}", self.stats.average_attempts())
-                    .bright_yellow()
-                    .bold(),
-                make_plural(self.stats.average_attempts().floor() as i32)
-            );
-            println!("The top 5 words you tried most frequently are:");
-            for (word, n) in self.stats.most_frequent() {
-                println!(
-                    "{}    {} time{}",
-                    word.bold(),
-                    n.to_string().bold(),
-                    make_plural(*n)
-                );
-            }
-        } else {
-            println!(
-                "{} {} {:.2}",
-                self.stats.success,
-                self.stats.failure,
-                self.stats.average_attempts()
+    ///Ranks all the words as the next guess by the expected information about the candidates, best first
+    ///Ties are broken in favor of the candidates, which may win at once, and then alphabetically
+    ///Before the first guess the ranking is cached, and computed again only if the word lists change
+    pub fn ranked_guesses(
+        &self,
+        candidates: &[String],
+        all_words: &BTreeSet<String>,
+    ) -> Vec<(String, f32)> {
+        let rank = || {
+            let candidate_set = candidates.iter().collect::<BTreeSet<_>>();
+            let mut counts = vec![0; 3usize.pow(self.word_length as u32)];
+            let mut ranking = all_words
+                .iter()
+                .filter(|word| word.len() == self.word_length)
+                .map(|word| {
+                    (
+                        word,
+                        entropy(word, candidates, &mut counts),
+                        candidate_set.contains(word),
+                    )
+                })
+                .collect::<Vec<_>>();
+            ranking.sort_by(
+                |(word1, entropy1, is_candidate1), (word2, entropy2, is_candidate2)| {
+                    entropy2
+                        .total_cmp(entropy1)
+                        .then_with(|| is_candidate2.cmp(is_candidate1))
+                        .then_with(|| word1.cmp(word2))
+                },
             );
-            let mut output = String::new();
-            for (word, n) in self.stats.most_frequent() {
-                output += &format!("{} {} ", word, n);
+            ranking
+                .into_iter()
+                .map(|(word, entropy, _)| (word.clone(), entropy))
+                .collect()
+        };
+
+        if !self.guesses.is_empty() {
+            return rank();
+        }
+        let mut hasher = DefaultHasher::new();
+        (self.word_length, candidates, all_words).hash(&mut hasher);
+        let key = hasher.finish();
+        let mut cache = FIRST_GUESS_RANKING.lock().unwrap();
+        match *cache {
+            Some((cached_key, ref ranking)) if cached_key == key => ranking.clone(),
+            _ => {
+                let ranking = rank();
+                *cache = Some((key, ranking.clone()));
+                ranking
             }
-            println!("{}", output.trim_end());
         }
     }
 
-    ///Accepts and processes the current guess for GUI mode
-    fn accept_current_guess(&mut self) {
-        self.game_state = self.current_game.accept_guess(
-            &self.current_guess,
-            &self.acceptables,
-            self.config.difficult,
-        );
-        self.current_guess = String::new();
-    }
-
-    ///Builds a key of the keyboard for GUI mode
-    ///Arguments: ch: &char -- the character of the key, ui: &mut egui::Ui -- the UI to build the key on
-    fn key(&mut self, ch: &char, ui: &mut egui::Ui) {
-        if ui
-            .add(
-                egui::Button::new(egui::RichText::new(*ch).size(28.0).color(colorize_gui(
-                    *self.current_game.letters_status.get(ch).unwrap(),
-                )))
-                .stroke(egui::Stroke {
-                    width: 2.0,
-                    color: colorize_gui(*self.current_game.letters_status.get(ch).unwrap()),
-                }),
-            )
-            .clicked()
-        {
-            self.current_guess.push(*ch);
+    ///Gets the guess which is expected to give the most information about the candidates
+    ///Returns: Option<String> -- the best guess, None if there is no candidate
+    pub fn best_next_guess(
+        &self,
+        candidates: &[String],
+        all_words: &BTreeSet<String>,
+    ) -> Option<String> {
+        match candidates {
+            [] => None,
+            [answer] => Some(answer.clone()),
+            _ => self
+                .ranked_guesses(candidates, all_words)
+                .into_iter()
+                .next()
+                .map(|(word, _)| word),
         }
     }
 
-    ///Builds the bottom panel for the GUI mode
-    fn bottom_panel(&mut self, ui: &mut egui::Ui) {
-        //Disable the panel if the game hasn't been initialized
-        match self.game_state {
-            GameState::Uninitialized => {
-                ui.set_enabled(false);
-            }
-            _ => (),
+    ///Chooses the answer of an adversarial game against a guess, keeping the player as confused as possible
+    ///The candidates are grouped by the status they would give to the guess, and the largest group is kept,
+    ///which leaves the most uncertainty; ties are broken in favor of fewer greens and then fewer yellows,
+    ///so the guess is only correct when it is the last candidate
+    ///Returns: String -- the first word of the group kept, the current answer if there is no candidate
+    fn adversarial_answer(&self, guess: &str, acceptables: &BTreeSet<String>) -> String {
+        let mut groups: BTreeMap<Vec<char>, Vec<String>> = BTreeMap::new();
+        for word in self.possible_words(acceptables) {
+            groups
+                .entry(guess_status(&word, guess))
+                .or_default()
+                .push(word);
         }
+        let count = |status: &[char], letter: char| status.iter().filter(|c| **c == letter).count();
+        groups
+            .into_iter()
+            .max_by_key(|(status, words)| {
+                (
+                    words.len(),
+                    std::cmp::Reverse(count(status, 'G')),
+                    std::cmp::Reverse(count(status, 'Y')),
+                )
+            })
+            .map(|(_, words)| words[0].clone())
+            .unwrap_or_else(|| self.answer.clone())
+    }
 
-        ui.add_space(5.0);
-        egui::Grid::new("keyboard")
-            .spacing(vec2(10.0, 10.0))
-            .show(ui, |ui| {
-                ui.columns(10, |columns| {
-                    for (col, ch) in columns.iter_mut().zip(KEYBOARD[..10].iter()) {
-                        self.key(ch, col);
-                    }
-                });
-
-                ui.end_row();
-
-                ui.columns(9, |columns| {
-                    for (col, ch) in columns.iter_mut().zip(KEYBOARD[10..19].iter()) {
-                        self.key(ch, col);
-                    }
-                });
-
-                ui.end_row();
-
-                ui.columns(9, |columns| {
-                    for (col, ch) in columns.iter_mut().skip(1).zip(KEYBOARD[19..].iter()) {
-                        self.key(ch, col);
-                    }
-                });
-
-                ui.end_row();
-            });
-        ui.add_space(5.0);
-    }
-
-    ///Builds the left panel for the GUI mode
-    fn left_panel(&mut self, ui: &mut egui::Ui) {
-        //Disable the panel if the game hasn't been initialized
-        match self.game_state {
-            GameState::Uninitialized => {
-                ui.set_enabled(false);
-            }
-            _ => (),
+    ///Gives up the current attempt without a guess, e.g. when the time to enter it runs out
+    ///The attempt counts against max_attempts like a guess, so the game is lost sooner,
+    ///while max_attempts itself is kept for the statistics and the share text
+    ///Returns: GameState -- Lost if no attempt is left, Continue otherwise
+    pub fn forfeit_attempt(&mut self, acceptables: &BTreeSet<String>) -> GameState {
+        self.forfeited += 1;
+        if self.attempts_used() < self.max_attempts {
+            return GameState::Continue;
+        }
+        //An adversarial game without any guess has no answer yet, any candidate is as good
+        if self.answer.is_empty() {
+            self.answer = self
+                .possible_words(acceptables)
+                .into_iter()
+                .next()
+                .unwrap_or_default();
         }
+        GameState::Lost
+    }
 
-        //Title
-        ui.add_space(5.0);
-        ui.label(
-            egui::RichText::new("Wordle")
-                .size(25.0)
-                .color(egui::Color32::WHITE),
-        );
-        ui.separator();
-
-        //Statistics
-        ui.label(
-            egui::RichText::new(format!("Played: {}", self.stats.total_rounds))
-                .size(20.0)
-                .color(egui::Color32::WHITE),
-        );
-        ui.label(
-            egui::RichText::new(format!("Won: {}", self.stats.success))
-                .size(20.0)
-                .color(egui::Color32::WHITE),
-        );
-        ui.label(
-            egui::RichText::new(format!("Lost: {}", self.stats.failure))
-                .size(20.0)
-                .color(egui::Color32::WHITE),
-        );
-
-        //Input area
-        ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
-            ui.add_space(5.0);
-            let response = ui.add_sized(
-                vec2(100.0, 30.0),
-                egui::TextEdit::singleline(&mut self.current_guess)
-                    .hint_text("Your guess")
-                    .font(egui::TextStyle::Heading),
-            );
-            if response.lost_focus() && ui.input().key_pressed(egui::Key::Enter) {
-                self.accept_current_guess();
-            }
-            ui.separator();
-        });
+    ///Counts the attempts used up, whether by a guess or given up
+    fn attempts_used(&self) -> usize {
+        self.guesses.len() + self.forfeited
     }
 
-    ///Builds the central panel for the GUI mode
-    fn central_panel(&mut self, ui: &mut egui::Ui) {
-        //Disable the panel if the game hasn't been initialized
-        match self.game_state {
-            GameState::Uninitialized => {
-                ui.set_enabled(false);
-            }
-            _ => (),
+    ///Checks whether a guess being typed in this game, in any case, is highlighted as breaking the rules
+    ///Only guesses in difficult mode are checked, by breaks_difficult_mode
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn highlights_guess(&self, guess: &str, is_difficult: bool) -> bool {
+        is_difficult
+            && breaks_difficult_mode(
+                &guess.to_ascii_uppercase(),
+                self.word_length,
+                &self.guesses,
+                &self.guesses_status,
+            )
+    }
+
+    ///Accepts and processes a new guess
+    ///In adversarial mode the answer is chosen again before the guess is evaluated
+    pub fn accept_guess(
+        &mut self,
+        guess: &str,
+        acceptables: &BTreeSet<String>,
+        is_difficult: bool,
+    ) -> GameState {
+        if guess.chars().count() != self.word_length {
+            return GameState::InvalidInput;
         }
 
-        //Builds the guesses status display area
-        egui::Grid::new("guesses")
-            .spacing(vec2(10.0, 10.0))
-            .show(ui, |ui| {
-                for a in 0..6 {
-                    for b in 0..5 {
-                        let ch = match self.current_game.guesses.get(a) {
-                            Some(s) => match s.chars().nth(b) {
-                                Some(c) => c,
-                                None => ' ',
-                            },
-                            None => ' ',
-                        };
-                        if ui
-                            .add_sized(
-                                vec2(45.0, 60.0),
-                                egui::Button::new(
-                                    egui::RichText::new(ch)
-                                        .size(40.0)
-                                        .color(colorize_gui(
-                                            self.current_game
-                                                .guesses_status
-                                                .get(a)
-                                                .unwrap_or(&vec!['X'; 5])[b],
-                                        ))
-                                        .text_style(egui::TextStyle::Heading),
-                                )
-                                .stroke(egui::Stroke {
-                                    width: 2.0,
-                                    color: colorize_gui(
-                                        self.current_game
-                                            .guesses_status
-                                            .get(a)
-                                            .unwrap_or(&vec!['X'; 5])[b],
-                                    ),
-                                }),
-                            )
-                            .clicked()
-                        {}
-                    }
-                    ui.end_row();
-                }
-            });
+        //Any candidate left is consistent with the previous guesses, so the answer may change
+        if self.adversarial && acceptables.contains(&guess.to_ascii_uppercase()) {
+            self.answer = self.adversarial_answer(&guess.to_ascii_uppercase(), acceptables);
+        }
+
+        let (guess_status, letters_status) = match evaluate_guess(
+            &self.answer,
+            guess,
+            acceptables,
+            is_difficult,
+            &self.guesses,
+            &self.guesses_status,
+            &self.letters_status,
+        ) {
+            Some(result) => result,
+            None => return GameState::InvalidInput,
+        };
+
+        //Stores the result
+        self.guesses.push(guess.to_ascii_uppercase());
+        self.guesses_status.push(guess_status);
+        self.letters_status = letters_status;
+
+        //Decides the game state
+        if self.guesses[self.guesses.len() - 1] == self.answer {
+            GameState::Won
+        } else if self.attempts_used() >= self.max_attempts {
+            GameState::Lost
+        } else {
+            GameState::Continue
+        }
     }
 }
 
-impl eframe::App for Wordle {
-    ///The main function for GUI mode
-    fn update(&mut self, context: &egui::Context, _frame: &mut eframe::Frame) {
-        //Builds the panels
-        egui::TopBottomPanel::bottom("keyboard")
-            .resizable(false)
-            .show(&context, |ui| self.bottom_panel(ui));
-
-        egui::SidePanel::left("info")
-            .default_width(100.0)
-            .resizable(false)
-            .show(&context, |ui| self.left_panel(ui));
-
-        egui::CentralPanel::default().show(&context, |ui| self.central_panel(ui));
-
-        //Indicators
-        let mut game_over_info_open = true;
-        let mut error_info_open = true;
-        let mut config_open = true;
-
-        //Reacts to the game state
-        match self.game_state {
-            GameState::Won => {
-                egui::Window::new("Information")
-                    .auto_sized()
-                    .open(&mut game_over_info_open)
-                    .show(context, |ui| {
-                        ui.label(
-                            egui::RichText::new("You win!")
-                                .size(25.0)
-                                .color(egui::Color32::WHITE),
-                        );
-                    });
-            }
-            GameState::Lost => {
-                egui::Window::new("Information")
-                    .auto_sized()
-                    .open(&mut game_over_info_open)
-                    .show(context, |ui| {
-                        ui.label(
-                            egui::RichText::new(format!(
-                                "You lose! Answer: {}",
-                                self.current_game.answer
-                            ))
-                            .size(25.0)
-                            .color(egui::Color32::WHITE),
-                        );
-                    });
-            }
-            GameState::InvalidInput => {
-                egui::Window::new("Information")
-                    .auto_sized()
-                    .open(&mut error_info_open)
-                    .show(context, |ui| {
-                        ui.label(
-                            egui::RichText::new("Invalid input!")
-                                .size(25.0)
-                                .color(egui::Color32::WHITE),
-                        );
-                    });
-            }
-            GameState::Uninitialized => {
-                //Initialization on launch
-                egui::Window::new("Configuration")
-                    .auto_sized()
-                    .open(&mut config_open)
-                    .show(context, |ui| {
-                        ui.checkbox(&mut self.config.difficult, "Difficult mode");
-                        if ui
-                            .add_sized(
-                                vec2(180.0, 20.0),
-                                egui::Button::new(
-                                    egui::RichText::new("Game data storage file")
-                                        .color(egui::Color32::WHITE),
-                                ),
-                            )
-                            .clicked()
-                        {
-                            if let Some(path) = rfd::FileDialog::new().pick_file() {
-                                self.stats_filename = path.display().to_string();
-                            }
-                        }
-                    });
-            }
-            GameState::Continue => {}
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        //Operations after the windows are closed
-        if !game_over_info_open {
-            self.game_state = GameState::Continue;
-            self.stats.record(self.current_game.clone());
-            self.current_game = Game::new(random_pick(&self.finals));
-            //Save the statistics to the given JSON file
-            if !self.stats_filename.is_empty() {
-                fs::write(&self.stats_filename, self.stats.to_json()).unwrap();
-            }
-        }
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|word| word.to_string()).collect()
+    }
 
-        if !error_info_open {
-            self.game_state = GameState::Continue;
+    #[test]
+    fn test_guess_editing() {
+        //A letter is added until the guess is complete, and no key works while a guess is revealed
+        let mut guess = String::new();
+        for ch in "CRANES".chars() {
+            push_letter(&mut guess, ch, 5, false);
         }
+        assert_eq!(guess, "CRANE");
+        push_letter(&mut guess, 'S', 6, true);
+        pop_letter(&mut guess, true);
+        assert_eq!(guess, "CRANE");
+        pop_letter(&mut guess, false);
+        assert_eq!(guess, "CRAN");
+
+        //The keys deleting letters are disabled if there is nothing to delete
+        assert!(can_delete_letters("C", false));
+        assert!(!can_delete_letters("C", true));
+        assert!(!can_delete_letters("", false));
+
+        //A guess typed in the input area is cut to the word length
+        assert_eq!(truncate_guess("slates", 5), "slate");
+        assert_eq!(truncate_guess("sla", 5), "sla");
+    }
 
-        if !config_open {
-            if !self.stats_filename.is_empty() {
-                let mut io_failure = false;
-                self.stats = match File::open(&self.stats_filename) {
-                    Ok(mut file) => {
-                        let mut json = String::new();
-                        match file.read_to_string(&mut json) {
-                            Ok(_) => match Stats::from_json(&json) {
-                                Ok(s) => s,
-                                Err(_) => {
-                                    io_failure = true;
-                                    Stats::new()
-                                }
-                            },
-                            Err(_) => {
-                                io_failure = true;
-                                Stats::new()
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        io_failure = true;
-                        Stats::new()
-                    }
-                };
-
-                if io_failure {
-                    egui::Window::new("Information")
-                        .auto_sized()
-                        .open(&mut error_info_open)
-                        .show(context, |ui| {
-                            ui.label(
-                                egui::RichText::new("Invalid game data storage file!")
-                                    .color(egui::Color32::WHITE),
-                            );
-                        });
-                }
-            }
-            self.game_state = GameState::Continue;
+    #[test]
+    fn test_difficult_mode_highlight() {
+        //After SLATE against CRANE, A and E are green
+        let acceptables = words(&["CRANE", "SLATE", "BROAD"])
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        let mut game = Game::new("CRANE", 6);
+        game.accept_guess("SLATE", &acceptables, true);
+
+        assert!(!game.highlights_guess("br", true));
+        //O takes the place of the green A, in any case
+        assert!(game.highlights_guess("bro", true));
+        assert!(game.highlights_guess("BRO", true));
+        //Nothing is highlighted out of difficult mode
+        assert!(!game.highlights_guess("bro", false));
+    }
+
+    #[test]
+    fn test_first_guess_ranking_follows_word_lists() {
+        //The ranking of other lists or another word length must not be taken from the cache
+        for (word_length, list) in [
+            (5, words(&["CRANE", "SLATE", "TRACE"])),
+            (5, words(&["ABBEY", "ABBOT", "ACORN"])),
+            (6, words(&["ABACUS", "ABBESS", "ABDUCT"])),
+            (5, words(&["CRANE", "SLATE", "TRACE"])),
+        ] {
+            let game = Game::new_adversarial(word_length, 6);
+            let all_words = list.iter().cloned().collect::<BTreeSet<_>>();
+            let ranking = game.ranked_guesses(&list, &all_words);
+            assert_eq!(ranking.len(), list.len());
+            assert!(ranking.iter().all(|(word, _)| all_words.contains(word)));
         }
     }
 }
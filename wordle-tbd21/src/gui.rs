@@ -0,0 +1,805 @@
+use super::{game::*, stats::*, util::*, *};
+use app::*;
+use chrono::Utc;
+use eframe::egui::{self, vec2};
+use std::fs::File;
+use std::time::{Duration, Instant};
+
+///Keys of the physical keyboard in the same order as LETTERS
+const LETTER_KEYS: [egui::Key; 26] = [
+    egui::Key::A,
+    egui::Key::B,
+    egui::Key::C,
+    egui::Key::D,
+    egui::Key::E,
+    egui::Key::F,
+    egui::Key::G,
+    egui::Key::H,
+    egui::Key::I,
+    egui::Key::J,
+    egui::Key::K,
+    egui::Key::L,
+    egui::Key::M,
+    egui::Key::N,
+    egui::Key::O,
+    egui::Key::P,
+    egui::Key::Q,
+    egui::Key::R,
+    egui::Key::S,
+    egui::Key::T,
+    egui::Key::U,
+    egui::Key::V,
+    egui::Key::W,
+    egui::Key::X,
+    egui::Key::Y,
+    egui::Key::Z,
+];
+
+///Time each tile of a guess takes to flip, the next tile starts flipping when it is done
+const TILE_FLIP: Duration = Duration::from_millis(100);
+
+///Reveal of the status of a guess, whose tiles are flipped one by one from left to right
+#[derive(Default)]
+pub struct AnimationState {
+    ///Index of the guess being revealed
+    row: usize,
+
+    ///Number of tiles in the row
+    columns: usize,
+
+    ///When the first tile started flipping, None if nothing has been revealed yet
+    started: Option<Instant>,
+}
+
+impl AnimationState {
+    ///Starts revealing the guess of the given index
+    pub fn start(row: usize, columns: usize) -> Self {
+        Self {
+            row,
+            columns,
+            started: Some(Instant::now()),
+        }
+    }
+
+    ///Checks whether some tiles are still to be flipped, during which the input is blocked
+    pub fn is_animating(&self) -> bool {
+        match self.started {
+            Some(started) => started.elapsed() < TILE_FLIP * self.columns as u32,
+            None => false,
+        }
+    }
+
+    ///Gets how far a tile has been flipped
+    ///Returns: f32 -- from 0.0 before the tile starts flipping to 1.0 once it shows its status
+    pub fn flip_progress(&self, row: usize, column: usize) -> f32 {
+        match self.started {
+            Some(started) if row == self.row && self.is_animating() => {
+                let elapsed =
+                    started.elapsed().as_secs_f32() - (TILE_FLIP * column as u32).as_secs_f32();
+                (elapsed / TILE_FLIP.as_secs_f32()).clamp(0.0, 1.0)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+///Time a notification of an earned achievement stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+///Notification of an earned achievement, in the bottom right corner of the window
+pub struct Toast {
+    achievement: Achievement,
+    shown: Instant,
+}
+
+///The tool function for colorizing characters according to their status
+pub fn colorize_gui(status: char) -> egui::Color32 {
+    match status {
+        'R' => egui::Color32::LIGHT_RED,
+        'Y' => egui::Color32::YELLOW,
+        'G' => egui::Color32::LIGHT_GREEN,
+        _ => egui::Color32::WHITE,
+    }
+}
+
+///The tool function for colorizing characters in the colorblind mode, blue and orange take the place of green and yellow
+pub fn colorize_gui_colorblind(status: char) -> egui::Color32 {
+    match status {
+        'R' => egui::Color32::DARK_GRAY,
+        'Y' => egui::Color32::from_rgb(230, 159, 0),
+        'G' => egui::Color32::from_rgb(0, 114, 178),
+        _ => egui::Color32::WHITE,
+    }
+}
+
+///Builds the button for starting a new game after the current one is over
+///Returns: bool -- whether the button is clicked
+fn play_again_button(ui: &mut egui::Ui) -> bool {
+    ui.add_sized(
+        vec2(180.0, 20.0),
+        egui::Button::new(egui::RichText::new("Play Again").color(egui::Color32::WHITE)),
+    )
+    .clicked()
+}
+
+impl Wordle {
+    ///Runs the Wordle game application in GUI mode
+    pub fn run_gui(self) {
+        let options = eframe::NativeOptions {
+            resizable: false,
+            initial_window_size: Some(self.window_size()),
+            ..Default::default()
+        };
+
+        eframe::run_native("Wordle", options, Box::new(|_cc| Box::new(self)));
+    }
+
+    ///Gets the window size fitting the grid of the configured game
+    fn window_size(&self) -> egui::Vec2 {
+        //Each row of the guesses takes 70 points
+        vec2(
+            (120.0 + 55.0 * self.config.word_length() as f32).max(395.0),
+            135.0 + 70.0 * self.config.max_attempts() as f32,
+        )
+    }
+
+    ///Makes the first game with the settings chosen in the configuration window
+    fn first_game(&self) -> Game {
+        //Stamped with the start time until Stats::record stamps the end time
+        Game {
+            played_at: Some(Utc::now()),
+            ..match self.config.adversarial {
+                true => {
+                    Game::new_adversarial(self.config.word_length(), self.config.max_attempts())
+                }
+                false => Game::new(random_pick(&self.finals, None), self.config.max_attempts()),
+            }
+        }
+    }
+
+    ///Accepts and processes the current guess for GUI mode
+    fn accept_current_guess(&mut self) {
+        if self.animation_state.is_animating() {
+            return;
+        }
+        self.game_state = self.current_game.accept_guess(
+            &self.current_guess,
+            &self.acceptables,
+            self.config.difficult,
+        );
+        if !matches!(self.game_state, GameState::InvalidInput) {
+            self.animation_state = AnimationState::start(
+                self.current_game.guesses.len() - 1,
+                self.current_game.word_length,
+            );
+        }
+        self.current_guess = String::new();
+        self.focus_guess_input = true;
+    }
+
+    ///Colorizes a status in the color mode of the configuration
+    fn colorize(&self, status: char) -> egui::Color32 {
+        if self.config.colorblind_mode {
+            colorize_gui_colorblind(status)
+        } else {
+            colorize_gui(status)
+        }
+    }
+
+    ///Builds a key of the keyboard for GUI mode
+    ///Arguments: ch: &char -- the character of the key, ui: &mut egui::Ui -- the UI to build the key on
+    fn key(&mut self, ch: &char, ui: &mut egui::Ui) {
+        if ui
+            .add(
+                egui::Button::new(
+                    egui::RichText::new(*ch)
+                        .size(28.0)
+                        .color(self.colorize(*self.current_game.letters_status.get(ch).unwrap())),
+                )
+                .stroke(egui::Stroke {
+                    width: 2.0,
+                    color: self.colorize(*self.current_game.letters_status.get(ch).unwrap()),
+                }),
+            )
+            .clicked()
+        {
+            self.push_letter(*ch);
+        }
+    }
+
+    ///Appends a letter to the current guess unless the guess is already complete
+    fn push_letter(&mut self, ch: char) {
+        push_letter(
+            &mut self.current_guess,
+            ch,
+            self.current_game.word_length,
+            self.animation_state.is_animating(),
+        );
+    }
+
+    ///Deletes the last letter of the current guess
+    fn pop_letter(&mut self) {
+        pop_letter(&mut self.current_guess, self.animation_state.is_animating());
+    }
+
+    ///Builds a key editing the current guess, wider than the letter keys and only enabled if there is a letter to delete
+    ///Returns: bool -- whether the key is clicked
+    fn edit_key(&self, text: &str, ui: &mut egui::Ui) -> bool {
+        //As high as the letter keys, whose text is 28 points
+        let height = ui.fonts().row_height(&egui::FontId::proportional(28.0))
+            + 2.0 * ui.spacing().button_padding.y;
+        let enabled = can_delete_letters(&self.current_guess, self.animation_state.is_animating());
+        ui.add_enabled_ui(enabled, |ui| {
+            ui.add_sized(
+                vec2(ui.available_width(), height),
+                egui::Button::new(egui::RichText::new(text).size(20.0)),
+            )
+            .clicked()
+        })
+        .inner
+    }
+
+    ///Handles the physical keyboard for GUI mode
+    ///Letters are typed into the current guess, Backspace deletes the last one and Enter submits
+    fn keyboard_input(&mut self, context: &egui::Context) {
+        //The keys only work during a game and are left to the input area while it has focus
+        match self.game_state {
+            GameState::Uninitialized | GameState::Won | GameState::Lost => return,
+            _ => (),
+        }
+        if context.wants_keyboard_input() || self.animation_state.is_animating() {
+            return;
+        }
+
+        let input = context.input();
+        for (key, ch) in LETTER_KEYS.iter().zip(LETTERS) {
+            if input.key_pressed(*key) {
+                self.push_letter(ch);
+            }
+        }
+        if input.key_pressed(egui::Key::Backspace) {
+            self.pop_letter();
+        }
+        if input.key_pressed(egui::Key::Enter) {
+            self.accept_current_guess();
+        }
+    }
+
+    ///Builds the bottom panel for the GUI mode
+    fn bottom_panel(&mut self, ui: &mut egui::Ui) {
+        //Disable the panel if the game hasn't been initialized
+        match self.game_state {
+            GameState::Uninitialized => {
+                ui.set_enabled(false);
+            }
+            _ => (),
+        }
+
+        ui.add_space(5.0);
+        egui::Grid::new("keyboard")
+            .spacing(vec2(10.0, 10.0))
+            .show(ui, |ui| {
+                ui.columns(10, |columns| {
+                    for (col, ch) in columns.iter_mut().zip(KEYBOARD[..10].iter()) {
+                        self.key(ch, col);
+                    }
+                });
+
+                ui.end_row();
+
+                //The default fonts have no glyph of "⌫", so Backspace is shown as an arrow
+                ui.columns(10, |columns| {
+                    for (col, ch) in columns.iter_mut().zip(KEYBOARD[10..19].iter()) {
+                        self.key(ch, col);
+                    }
+                    if self.edit_key("⬅", &mut columns[9]) {
+                        self.pop_letter();
+                    }
+                });
+
+                ui.end_row();
+
+                ui.columns(9, |columns| {
+                    for (col, ch) in columns.iter_mut().skip(1).zip(KEYBOARD[19..].iter()) {
+                        self.key(ch, col);
+                    }
+                    if self.edit_key("Clear", &mut columns[8]) {
+                        self.current_guess.clear();
+                    }
+                });
+
+                ui.end_row();
+            });
+        ui.add_space(5.0);
+    }
+
+    ///Builds the left panel for the GUI mode
+    fn left_panel(&mut self, ui: &mut egui::Ui) {
+        //Disable the panel if the game hasn't been initialized
+        match self.game_state {
+            GameState::Uninitialized => {
+                ui.set_enabled(false);
+            }
+            _ => (),
+        }
+
+        //Title
+        ui.add_space(5.0);
+        ui.label(
+            egui::RichText::new("Wordle")
+                .size(25.0)
+                .color(egui::Color32::WHITE),
+        );
+        ui.separator();
+
+        //Statistics
+        self.show_stats_panel(ui);
+
+        //Input area
+        let animating = self.animation_state.is_animating();
+        ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
+            ui.set_enabled(!animating);
+            ui.add_space(5.0);
+            //The border turns red as soon as the guess can not be accepted in difficult mode
+            let breaks_rules = self
+                .current_game
+                .highlights_guess(&self.current_guess, self.config.difficult);
+            let response = ui
+                .scope(|ui| {
+                    if breaks_rules {
+                        let stroke = egui::Stroke::new(2.0, egui::Color32::RED);
+                        let visuals = ui.visuals_mut();
+                        visuals.selection.stroke = stroke;
+                        visuals.widgets.inactive.bg_stroke = stroke;
+                        visuals.widgets.hovered.bg_stroke = stroke;
+                        visuals.widgets.active.bg_stroke = stroke;
+                    }
+                    ui.add_sized(
+                        vec2(100.0, 30.0),
+                        egui::TextEdit::singleline(&mut self.current_guess)
+                            .hint_text("Your guess")
+                            .font(egui::TextStyle::Heading),
+                    )
+                })
+                .inner;
+            if response.changed() {
+                self.current_guess =
+                    truncate_guess(&self.current_guess, self.current_game.word_length);
+            }
+            if response.lost_focus() && ui.input().key_pressed(egui::Key::Enter) {
+                self.accept_current_guess();
+            }
+            //Takes the focus back once a game is going on, so guesses can be typed one after another
+            if self.focus_guess_input && !animating {
+                match self.game_state {
+                    GameState::Continue | GameState::InvalidInput => {
+                        response.request_focus();
+                        self.focus_guess_input = false;
+                    }
+                    _ => (),
+                }
+            }
+            ui.separator();
+        });
+    }
+
+    ///Builds the statistics in the left panel, the guess distribution as a bar chart above the streaks
+    ///It is built from self.stats every frame, so it follows each recorded game
+    fn show_stats_panel(&mut self, ui: &mut egui::Ui) {
+        //The longest bar is 60 points wide, any won count gets at least a sliver
+        let distribution = self.stats.guess_distribution();
+        let most = distribution.iter().copied().max().unwrap_or(0).max(1);
+        egui::Grid::new("distribution")
+            .spacing(vec2(5.0, 5.0))
+            .show(ui, |ui| {
+                for (i, count) in distribution.iter().enumerate() {
+                    ui.label(
+                        egui::RichText::new((i + 1).to_string())
+                            .size(15.0)
+                            .color(egui::Color32::WHITE),
+                    );
+                    ui.horizontal(|ui| {
+                        let width = if *count == 0 {
+                            0.0
+                        } else {
+                            (60.0 * *count as f32 / most as f32).max(3.0)
+                        };
+                        let (rect, _) =
+                            ui.allocate_exact_size(vec2(width, 15.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 2.0, self.colorize('G'));
+                        ui.label(
+                            egui::RichText::new(count.to_string())
+                                .size(15.0)
+                                .color(egui::Color32::WHITE),
+                        );
+                    });
+                    ui.end_row();
+                }
+            });
+
+        ui.add_space(5.0);
+        ui.label(
+            egui::RichText::new(format!("Streak: {}", self.stats.current_streak))
+                .size(20.0)
+                .color(egui::Color32::WHITE),
+        );
+        ui.label(
+            egui::RichText::new(format!("Best streak: {}", self.stats.best_streak))
+                .size(20.0)
+                .color(egui::Color32::WHITE),
+        );
+
+        //Clears the statistics, including those in the game data storage file
+        ui.add_space(5.0);
+        if ui
+            .button(egui::RichText::new("Reset statistics").color(egui::Color32::WHITE))
+            .clicked()
+        {
+            self.stats.reset();
+            self.save_stats();
+        }
+    }
+
+    ///Saves the statistics together with the configuration window settings to the given JSON file
+    fn save_stats(&mut self) {
+        if !self.stats_filename.is_empty() {
+            self.stats.settings = Some(AppSettings {
+                difficult: self.config.difficult,
+                stats_filename: self.stats_filename.clone(),
+                max_attempts: self.config.max_attempts,
+                word_length: self.config.word_length,
+                high_contrast: self.config.colorblind_mode,
+            });
+            save_stats(&self.stats_filename, &self.stats).unwrap();
+        }
+    }
+
+    ///Builds the central panel for the GUI mode
+    fn central_panel(&mut self, ui: &mut egui::Ui) {
+        //Disable the panel if the game hasn't been initialized
+        match self.game_state {
+            GameState::Uninitialized => {
+                ui.set_enabled(false);
+            }
+            _ => (),
+        }
+
+        //Builds the guesses status display area
+        egui::Grid::new("guesses")
+            .spacing(vec2(10.0, 10.0))
+            .show(ui, |ui| {
+                for a in 0..self.current_game.max_attempts {
+                    //The row after the last guess shows the guess being typed
+                    let in_progress = a == self.current_game.guesses.len();
+                    for b in 0..self.current_game.word_length {
+                        //The tile shrinks to a line and grows back, turning to its status color when halfway
+                        let progress = self.animation_state.flip_progress(a, b);
+                        let status = match progress < 0.5 {
+                            true => 'X',
+                            false => self
+                                .current_game
+                                .guesses_status
+                                .get(a)
+                                .map_or('X', |status| status[b]),
+                        };
+                        let height = (60.0 * (1.0 - 2.0 * progress).abs()).max(2.0);
+                        let ch = match self.current_game.guesses.get(a) {
+                            Some(s) => match s.chars().nth(b) {
+                                Some(c) => c,
+                                None => ' ',
+                            },
+                            None if in_progress => self
+                                .current_guess
+                                .to_ascii_uppercase()
+                                .chars()
+                                .nth(b)
+                                .unwrap_or(' '),
+                            None => ' ',
+                        };
+                        //Keeps the row at its full height while a tile is flipped
+                        ui.allocate_ui(vec2(45.0, 60.0), |ui| {
+                            ui.centered_and_justified(|ui| {
+                                ui.add_sized(
+                                    vec2(45.0, height),
+                                    egui::Button::new(
+                                        egui::RichText::new(ch)
+                                            .size(40.0 * height / 60.0)
+                                            .color(self.colorize(status))
+                                            .text_style(egui::TextStyle::Heading),
+                                    )
+                                    .stroke(egui::Stroke {
+                                        width: 2.0,
+                                        color: if in_progress {
+                                            egui::Color32::LIGHT_BLUE
+                                        } else {
+                                            self.colorize(status)
+                                        },
+                                    }),
+                                );
+                            });
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    ///Shows the notifications of the achievements earned lately, the newest at the bottom
+    fn show_toasts(&mut self, context: &egui::Context) {
+        self.toasts
+            .retain(|toast| toast.shown.elapsed() < TOAST_DURATION);
+        if self.toasts.is_empty() {
+            return;
+        }
+        egui::Area::new("toasts")
+            .anchor(egui::Align2::RIGHT_BOTTOM, vec2(-10.0, -10.0))
+            .show(context, |ui| {
+                for toast in &self.toasts {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "Achievement earned: {}",
+                                toast.achievement.name
+                            ))
+                            .strong(),
+                        );
+                        ui.label(&toast.achievement.description);
+                    });
+                }
+            });
+        //Draws again to hide the notifications once they expire
+        context.request_repaint_after(TOAST_DURATION);
+    }
+}
+
+impl eframe::App for Wordle {
+    ///The main function for GUI mode
+    fn update(&mut self, context: &egui::Context, frame: &mut eframe::Frame) {
+        self.keyboard_input(context);
+
+        //Keeps drawing the frames of the flip until the whole row is revealed
+        let animating = self.animation_state.is_animating();
+        if animating {
+            context.request_repaint_after(TILE_FLIP / 4);
+        }
+
+        //Builds the panels
+        egui::TopBottomPanel::bottom("keyboard")
+            .resizable(false)
+            .show(&context, |ui| self.bottom_panel(ui));
+
+        egui::SidePanel::left("info")
+            .default_width(100.0)
+            .resizable(false)
+            .show(&context, |ui| self.left_panel(ui));
+
+        egui::CentralPanel::default().show(&context, |ui| self.central_panel(ui));
+
+        self.show_toasts(context);
+
+        //Indicators
+        let mut play_again = false;
+        let mut error_info_open = true;
+        let mut config_open = true;
+
+        //Reacts to the game state, after the status of the guess is revealed
+        match self.game_state {
+            _ if animating => {}
+            GameState::Won => {
+                //The window stays until the player chooses to play again
+                egui::Window::new("Information")
+                    .auto_sized()
+                    .collapsible(false)
+                    .show(context, |ui| {
+                        ui.label(
+                            egui::RichText::new("You win!")
+                                .size(25.0)
+                                .color(egui::Color32::WHITE),
+                        );
+                        play_again = play_again_button(ui);
+                    });
+            }
+            GameState::Lost => {
+                egui::Window::new("Information")
+                    .auto_sized()
+                    .collapsible(false)
+                    .show(context, |ui| {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "You lose! Answer: {}",
+                                self.current_game.answer
+                            ))
+                            .size(25.0)
+                            .color(egui::Color32::WHITE),
+                        );
+                        play_again = play_again_button(ui);
+                    });
+            }
+            GameState::InvalidInput => {
+                egui::Window::new("Information")
+                    .auto_sized()
+                    .open(&mut error_info_open)
+                    .show(context, |ui| {
+                        ui.label(
+                            egui::RichText::new("Invalid input!")
+                                .size(25.0)
+                                .color(egui::Color32::WHITE),
+                        );
+                    });
+            }
+            GameState::Uninitialized => {
+                //Initialization on launch
+                egui::Window::new("Configuration")
+                    .auto_sized()
+                    .open(&mut config_open)
+                    .show(context, |ui| {
+                        let (finals_count, acceptables_count) = self.word_list_sizes();
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{} answers, {} acceptable words",
+                                finals_count, acceptables_count
+                            ))
+                            .color(egui::Color32::WHITE),
+                        );
+                        //Only the lengths of the final words can be chosen
+                        let mut word_length = self.config.word_length();
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new("Word length").color(egui::Color32::WHITE),
+                            );
+                            egui::ComboBox::from_id_source("word_length")
+                                .selected_text(word_length.to_string())
+                                .show_ui(ui, |ui| {
+                                    for length in self.word_lengths() {
+                                        ui.selectable_value(
+                                            &mut word_length,
+                                            length,
+                                            length.to_string(),
+                                        );
+                                    }
+                                });
+                        });
+                        if word_length != self.config.word_length() {
+                            self.config.word_length = Some(word_length);
+                        }
+                        //Practice mode has unlimited attempts, which the grid can't show
+                        if !self.config.practice {
+                            let mut max_attempts = self.config.max_attempts();
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new("Max guesses").color(egui::Color32::WHITE),
+                                );
+                                ui.add(egui::Slider::new(&mut max_attempts, 1..=10));
+                            });
+                            if max_attempts != self.config.max_attempts() {
+                                self.config.max_attempts = Some(max_attempts);
+                            }
+                        }
+                        ui.checkbox(&mut self.config.difficult, "Difficult mode");
+                        ui.checkbox(&mut self.config.colorblind_mode, "High contrast colors");
+                        if ui
+                            .add_sized(
+                                vec2(180.0, 20.0),
+                                egui::Button::new(
+                                    egui::RichText::new("Game data storage file")
+                                        .color(egui::Color32::WHITE),
+                                ),
+                            )
+                            .clicked()
+                        {
+                            if let Some(path) = rfd::FileDialog::new().pick_file() {
+                                self.stats_filename = path.display().to_string();
+                            }
+                        }
+                        if ui
+                            .add_sized(
+                                vec2(180.0, 20.0),
+                                egui::Button::new(
+                                    egui::RichText::new("Reset to defaults")
+                                        .color(egui::Color32::WHITE),
+                                ),
+                            )
+                            .clicked()
+                        {
+                            self.config.difficult = false;
+                            self.config.colorblind_mode = false;
+                            self.config.max_attempts = None;
+                            self.config.word_length = None;
+                            self.stats_filename.clear();
+                        }
+                    });
+
+                //A length without final words, e.g. the default one with custom lists, can't be played
+                let word_lengths = self.word_lengths();
+                if !word_lengths.contains(&self.config.word_length()) {
+                    self.config.word_length = word_lengths.first().copied();
+                }
+
+                //The grid and the window follow the number of guesses and the length chosen
+                if self.config.max_attempts() != self.current_game.max_attempts
+                    || self.config.word_length() != self.current_game.word_length
+                {
+                    self.current_game = Game::new_adversarial(
+                        self.config.word_length(),
+                        self.config.max_attempts(),
+                    );
+                    frame.set_window_size(self.window_size());
+                }
+            }
+            GameState::Continue => {}
+        }
+
+        //Operations after the windows are closed
+        if play_again {
+            self.game_state = GameState::Continue;
+            self.focus_guess_input = true;
+            let earned = self.stats.record(self.current_game.clone());
+            self.toasts
+                .extend(earned.into_iter().map(|achievement| Toast {
+                    achievement,
+                    shown: Instant::now(),
+                }));
+            self.current_game = match self.config.adversarial {
+                true => {
+                    Game::new_adversarial(self.config.word_length(), self.config.max_attempts())
+                }
+                false => Game::new(
+                    random_pick(&self.finals, Some(&self.current_game.answer)),
+                    self.config.max_attempts(),
+                ),
+            };
+            self.save_stats();
+        }
+
+        if !error_info_open {
+            self.game_state = GameState::Continue;
+        }
+
+        if !config_open {
+            if !self.stats_filename.is_empty() {
+                let mut io_failure = false;
+                self.stats = match File::open(&self.stats_filename) {
+                    Ok(mut file) => {
+                        let mut json = String::new();
+                        match file.read_to_string(&mut json) {
+                            Ok(_) => match Stats::from_json(&json) {
+                                Ok(s) => s,
+                                Err(_) => {
+                                    io_failure = true;
+                                    Stats::new()
+                                }
+                            },
+                            Err(_) => {
+                                io_failure = true;
+                                Stats::new()
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        io_failure = true;
+                        Stats::new()
+                    }
+                };
+
+                if io_failure {
+                    egui::Window::new("Information")
+                        .auto_sized()
+                        .open(&mut error_info_open)
+                        .show(context, |ui| {
+                            ui.label(
+                                egui::RichText::new("Invalid game data storage file!")
+                                    .color(egui::Color32::WHITE),
+                            );
+                        });
+                }
+            }
+            //Only the words of the length chosen are played from now on
+            let word_length = self.config.word_length();
+            self.finals.retain(|word| word.len() == word_length);
+            self.acceptables.retain(|word| word.len() == word_length);
+            self.current_game = self.first_game();
+            self.game_state = GameState::Continue;
+            self.focus_guess_input = true;
+        }
+    }
+}
@@ -1,13 +1,17 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
+mod app;
 mod builtin_words;
 mod game;
+#[cfg(feature = "gui")]
+mod gui;
+mod server;
 mod stats;
 mod util;
 
+use app::*;
 use builtin_words::*;
-use clap::Parser;
-use game::*;
+use clap::{Parser, Subcommand};
 use rand::prelude::*;
 use rand::seq::SliceRandom;
 use serde::Deserialize;
@@ -23,6 +27,10 @@ pub const LETTERS: [char; 26] = [
     'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
 ];
 
+pub const DEFAULT_WORD_LENGTH: usize = 5;
+
+pub const DEFAULT_MAX_ATTEMPTS: usize = 6;
+
 pub const KEYBOARD: [char; 26] = [
     'Q', 'W', 'E', 'R', 'T', 'Y', 'U', 'I', 'O', 'P', 'A', 'S', 'D', 'F', 'G', 'H', 'J', 'K', 'L',
     'Z', 'X', 'C', 'V', 'B', 'N', 'M',
@@ -30,6 +38,8 @@ pub const KEYBOARD: [char; 26] = [
 
 ///Wordle game config
 ///Can be parsed from either command-line arguments or a JSON file
+///The arguments override the JSON file, where null is the same as leaving an option out
+///`--no-word`, `--no-day` and `--no-seed` drop the answer, day or seed given in the JSON file
 #[derive(Deserialize, Parser, Clone)]
 #[clap(
     author = "TANG Bingda",
@@ -58,18 +68,50 @@ pub struct Config {
     #[clap(short = 'S', long, action)]
     state: Option<String>,
 
+    #[serde(default)]
+    #[clap(long = "import-csv", value_parser)]
+    import_csv: Option<String>,
+
+    #[serde(default)]
+    #[clap(long = "export-csv", value_parser)]
+    export_csv: Option<String>,
+
+    #[serde(default)]
+    #[clap(long = "import-nyt", value_parser)]
+    import_nyt: Option<String>,
+
+    ///Resumes the game from a code made by Game::encode_state, only given in the arguments
+    #[serde(skip, default)]
+    #[clap(long, value_parser)]
+    resume: Option<String>,
+
     #[serde(default)]
     #[clap(short, long, value_parser)]
     word: Option<String>,
 
+    ///Ignores the word in the JSON file
+    #[serde(skip, default)]
+    #[clap(long = "no-word", action, conflicts_with = "word")]
+    no_word: bool,
+
     #[serde(default)]
     #[clap(short, long, value_parser)]
     day: Option<usize>,
 
+    ///Ignores the day in the JSON file
+    #[serde(skip, default)]
+    #[clap(long = "no-day", action, conflicts_with = "day")]
+    no_day: bool,
+
     #[serde(default)]
     #[clap(short, long, value_parser)]
     seed: Option<u64>,
 
+    ///Ignores the seed in the JSON file
+    #[serde(skip, default)]
+    #[clap(long = "no-seed", action, conflicts_with = "seed")]
+    no_seed: bool,
+
     #[serde(default)]
     #[clap(short, long = "final-set", value_parser)]
     final_set: Option<String>,
@@ -78,15 +120,122 @@ pub struct Config {
     #[clap(short, long = "acceptable-set", value_parser)]
     acceptable_set: Option<String>,
 
+    #[serde(default)]
+    #[clap(long = "word-length", value_parser)]
+    word_length: Option<usize>,
+
+    #[serde(default)]
+    #[clap(long = "max-attempts", value_parser)]
+    max_attempts: Option<usize>,
+
+    #[serde(default)]
+    #[clap(long = "analyze-wordlist", action)]
+    analyze_wordlist: bool,
+
+    #[serde(default)]
+    #[clap(long = "list-words", action)]
+    list_words: bool,
+
+    #[serde(default)]
+    #[clap(long = "list-acceptable", action)]
+    list_acceptable: bool,
+
+    #[serde(default)]
+    #[clap(long = "no-color", action)]
+    no_color: bool,
+
+    #[serde(default)]
+    #[clap(long = "colorblind", action)]
+    colorblind_mode: bool,
+
+    #[serde(default)]
+    #[clap(long, action)]
+    share: bool,
+
+    #[serde(default)]
+    #[clap(long = "show-candidates", action)]
+    show_candidates: bool,
+
+    #[serde(default)]
+    #[clap(long, action)]
+    hint: bool,
+
+    #[serde(default)]
+    #[clap(long, action)]
+    adversarial: bool,
+
+    #[serde(default)]
+    #[clap(long, action)]
+    verbose: bool,
+
+    #[serde(default)]
+    #[clap(long, action)]
+    practice: bool,
+
+    #[serde(default)]
+    #[clap(long = "word-stats", value_parser)]
+    word_stats: Option<String>,
+
+    #[serde(default)]
+    #[clap(long = "letter-heatmap", action)]
+    letter_heatmap: bool,
+
+    #[serde(default)]
+    #[clap(long, value_parser)]
+    server: Option<u16>,
+
+    #[serde(default)]
+    #[clap(long = "reset-stats", action)]
+    reset_stats: bool,
+
+    ///Seconds given to enter each guess in non-GUI mode, the attempt is lost when they run out
+    #[serde(default)]
+    #[clap(long = "timeout", value_parser)]
+    guess_timeout: Option<u64>,
+
     #[serde(skip, default)]
     #[clap(short, long, value_parser)]
     config: Option<String>,
 
+    #[serde(skip, default)]
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     #[serde(skip, default)]
     #[clap(skip)]
     is_tty: bool,
 }
 
+///Subcommands which run instead of the game
+#[derive(Subcommand, Clone)]
+pub enum Command {
+    ///Plays with the best guesses without user input
+    Solve {
+        ///The answer to play against, a random final word if not given
+        #[clap(long, value_parser)]
+        answer: Option<String>,
+
+        ///Plays against every final word and reports the number of guesses
+        #[clap(long, action, conflicts_with = "answer")]
+        benchmark: bool,
+    },
+}
+
+impl Config {
+    ///Gets the number of letters in a word, 5 unless specified
+    pub fn word_length(&self) -> usize {
+        self.word_length.unwrap_or(DEFAULT_WORD_LENGTH)
+    }
+
+    ///Gets the number of attempts allowed in a game, 6 unless specified, or unlimited for practice
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts.unwrap_or(match self.practice {
+            true => usize::MAX,
+            false => DEFAULT_MAX_ATTEMPTS,
+        })
+    }
+}
+
 /// The main function for the Wordle game
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     //Initializes the configuration of the program from command-line arguments
@@ -107,18 +256,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     Some(_) => args.state,
                     None => json.state,
                 },
+                import_csv: match args.import_csv {
+                    Some(_) => args.import_csv,
+                    None => json.import_csv,
+                },
+                export_csv: match args.export_csv {
+                    Some(_) => args.export_csv,
+                    None => json.export_csv,
+                },
+                import_nyt: match args.import_nyt {
+                    Some(_) => args.import_nyt,
+                    None => json.import_nyt,
+                },
+                resume: args.resume,
                 word: match args.word {
                     Some(_) => args.word,
+                    None if args.no_word => None,
                     None => json.word,
                 },
+                no_word: args.no_word,
                 day: match args.day {
                     Some(_) => args.day,
+                    None if args.no_day => None,
                     None => json.day,
                 },
+                no_day: args.no_day,
                 seed: match args.seed {
                     Some(_) => args.seed,
+                    None if args.no_seed => None,
                     None => json.seed,
                 },
+                no_seed: args.no_seed,
                 final_set: match args.final_set {
                     Some(_) => args.final_set,
                     None => json.final_set,
@@ -127,27 +295,140 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     Some(_) => args.acceptable_set,
                     None => json.acceptable_set,
                 },
+                word_length: match args.word_length {
+                    Some(_) => args.word_length,
+                    None => json.word_length,
+                },
+                max_attempts: match args.max_attempts {
+                    Some(_) => args.max_attempts,
+                    None => json.max_attempts,
+                },
+                analyze_wordlist: args.analyze_wordlist || json.analyze_wordlist,
+                list_words: args.list_words || json.list_words,
+                list_acceptable: args.list_acceptable || json.list_acceptable,
+                no_color: args.no_color || json.no_color,
+                colorblind_mode: args.colorblind_mode || json.colorblind_mode,
+                share: args.share || json.share,
+                show_candidates: args.show_candidates || json.show_candidates,
+                hint: args.hint || json.hint,
+                adversarial: args.adversarial || json.adversarial,
+                verbose: args.verbose || json.verbose,
+                practice: args.practice || json.practice,
+                letter_heatmap: args.letter_heatmap || json.letter_heatmap,
+                reset_stats: args.reset_stats || json.reset_stats,
+                guess_timeout: match args.guess_timeout {
+                    Some(_) => args.guess_timeout,
+                    None => json.guess_timeout,
+                },
+                word_stats: match args.word_stats {
+                    Some(_) => args.word_stats,
+                    None => json.word_stats,
+                },
+                server: match args.server {
+                    Some(_) => args.server,
+                    None => json.server,
+                },
                 config: None,
+                command: args.command,
                 is_tty,
             }
         }
         None => args,
     };
 
-    //Initializes wordlists
+    //Disables ANSI colors if requested by the flag or by a non-empty NO_COLOR (see no-color.org)
+    if config.no_color || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        colored::control::set_override(false);
+    }
+
+    //The GUI has a row for every attempt, so practice is limited to the command line
+    if config.practice && config.gui {
+        invalid_arguments(is_tty, "`--practice` conflicts with `--gui`");
+    }
+
+    //The statistics to clear are those in the state file
+    if config.reset_stats && config.state.is_none() {
+        invalid_arguments(is_tty, "`--reset-stats` requires `--state`");
+    }
+
+    //The imported games are merged into the state file
+    if config.import_nyt.is_some() && config.state.is_none() {
+        invalid_arguments(is_tty, "`--import-nyt` requires `--state`");
+    }
+
+    //The code decides the answer, and only the command line plays on from it
+    if config.resume.is_some()
+        && (config.word.is_some() || config.random || config.adversarial || config.gui)
+    {
+        invalid_arguments(
+            is_tty,
+            "`--resume` conflicts with `--word`, `--random`, `--adversarial` and `--gui`",
+        );
+    }
+
+    if config.max_attempts() == 0 {
+        invalid_arguments(is_tty, "`--max-attempts` must be at least 1");
+    }
+
+    if config.guess_timeout == Some(0) {
+        invalid_arguments(is_tty, "`--timeout` must be at least 1");
+    }
+
+    let word_length = config.word_length();
+    if !(3..=10).contains(&word_length) {
+        invalid_arguments(is_tty, "`--word-length` must be between 3 and 10");
+    }
+    if word_length != DEFAULT_WORD_LENGTH
+        && (config.final_set.is_none() || config.acceptable_set.is_none())
+    {
+        invalid_arguments(
+            is_tty,
+            "the built-in word lists only have 5-letter words, `--word-length` requires `--final-set` and `--acceptable-set`",
+        );
+    }
+
+    //The answer of an adversarial game is never given in advance
+    if config.adversarial && (config.word.is_some() || config.random) {
+        invalid_arguments(
+            is_tty,
+            "`--adversarial` conflicts with `--word` and `--random`",
+        );
+    }
+
+    //Initializes wordlists, counting the words listed more than once for the analysis
+    let (mut acceptable_duplicates, mut final_duplicates) = (0, 0);
     let acceptables = match config.acceptable_set {
         Some(ref filename) => {
             let reader = BufReader::new(fs::File::open(filename)?);
-            let v = reader
+            let words = reader
                 .lines()
                 .map(|s| {
                     let word = s.unwrap().trim().to_ascii_uppercase();
-                    if word.len() > 5 {
-                        invalid_arguments(is_tty);
+                    //Only the letters A to Z can be colored
+                    if !word.bytes().all(|c| c.is_ascii_uppercase()) {
+                        invalid_arguments(
+                            is_tty,
+                            &format!(
+                                "acceptable word '{}' has characters other than letters",
+                                word
+                            ),
+                        );
+                    }
+                    //The GUI lets the player choose among the lengths of the words
+                    if word.len() != word_length && !config.gui {
+                        invalid_arguments(
+                            is_tty,
+                            &format!(
+                                "acceptable word '{}' does not have {} letters",
+                                word, word_length
+                            ),
+                        );
                     }
                     word
                 })
-                .collect::<BTreeSet<_>>();
+                .collect::<Vec<_>>();
+            let v = words.iter().cloned().collect::<BTreeSet<_>>();
+            acceptable_duplicates = words.len() - v.len();
             v
         }
         None => ACCEPTABLE.iter().map(|s| s.to_ascii_uppercase()).collect(),
@@ -156,21 +437,127 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let finals = match config.final_set {
         Some(ref filename) => {
             let reader = BufReader::new(fs::File::open(filename)?);
-            let v = reader
+            let words = reader
                 .lines()
                 .map(|s| {
                     let word = s.unwrap().trim().to_ascii_uppercase();
-                    if word.len() > 5 || !acceptables.contains(&word) {
-                        invalid_arguments(is_tty);
+                    //Only the letters A to Z can be colored
+                    if !word.bytes().all(|c| c.is_ascii_uppercase()) {
+                        invalid_arguments(
+                            is_tty,
+                            &format!("final word '{}' has characters other than letters", word),
+                        );
+                    }
+                    //The GUI lets the player choose among the lengths of the words
+                    if word.len() != word_length && !config.gui {
+                        invalid_arguments(
+                            is_tty,
+                            &format!(
+                                "final word '{}' does not have {} letters",
+                                word, word_length
+                            ),
+                        );
+                    }
+                    if !acceptables.contains(&word) {
+                        invalid_arguments(
+                            is_tty,
+                            &format!("final word '{}' is not in the acceptable set", word),
+                        );
                     }
                     word
                 })
-                .collect::<BTreeSet<_>>();
+                .collect::<Vec<_>>();
+            let v = words.iter().cloned().collect::<BTreeSet<_>>();
+            final_duplicates = words.len() - v.len();
             v.into_iter().collect::<Vec<_>>()
         }
         None => FINAL.iter().map(|s| s.to_ascii_uppercase()).collect(),
     };
 
+    //No game can be played without words, whichever list is empty
+    if acceptables.is_empty() {
+        invalid_arguments(is_tty, "the acceptable set is empty");
+    }
+    if finals.is_empty() {
+        invalid_arguments(is_tty, "the final set is empty");
+    }
+
+    //Prints statistics of the word lists instead of starting a game
+    if config.analyze_wordlist {
+        analyze_wordlist(
+            &finals,
+            &acceptables,
+            final_duplicates,
+            acceptable_duplicates,
+        );
+        return Ok(());
+    }
+
+    //Prints the loaded word lists instead of starting a game
+    if config.list_words {
+        list_words(&finals);
+        return Ok(());
+    }
+    if config.list_acceptable {
+        list_words(&acceptables);
+        return Ok(());
+    }
+
+    //Prints the statistics of an answer in the previous games instead of starting a game
+    if let Some(ref word) = config.word_stats {
+        print_word_stats(&load_stats(&config), word, is_tty);
+        return Ok(());
+    }
+
+    //Prints the letters of the previous games by position instead of starting a game
+    if config.letter_heatmap {
+        print_letter_heatmap(&load_stats(&config), is_tty);
+        return Ok(());
+    }
+
+    //Runs the solver instead of starting a game
+    if let Some(Command::Solve { answer, benchmark }) = &config.command {
+        let finals = finals.iter().cloned().collect::<BTreeSet<_>>();
+        if *benchmark {
+            if !benchmark_solver(
+                &finals,
+                &acceptables,
+                config.max_attempts(),
+                config.difficult,
+            ) {
+                std::process::exit(1);
+            }
+        } else {
+            let answer = match answer {
+                Some(word) => {
+                    let word = word.to_ascii_uppercase();
+                    if !finals.contains(&word) {
+                        invalid_arguments(
+                            is_tty,
+                            &format!("answer '{}' is not in the final set", word),
+                        );
+                    }
+                    word
+                }
+                None => random_pick(&finals.iter().cloned().collect(), None).to_string(),
+            };
+            solve(
+                &answer,
+                &finals,
+                &acceptables,
+                config.max_attempts(),
+                config.difficult,
+            );
+        }
+        return Ok(());
+    }
+
+    //Serves the games over HTTP instead of starting a game
+    if let Some(port) = config.server {
+        server::run_server(port, finals, acceptables, config)?;
+        return Ok(());
+    }
+
     //Starts Wordle game
     Wordle::new(finals, acceptables, config).run();
     Ok(())
@@ -0,0 +1,410 @@
+use super::game::*;
+use super::stats::Stats;
+use super::util::random_pick;
+use super::Config;
+use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+///How long an unfinished game is kept without any request for it
+const GAME_TTL: Duration = Duration::from_secs(30 * 60);
+
+///How long a finished game is kept, so that its final state can still be read
+const FINISHED_GAME_TTL: Duration = Duration::from_secs(60);
+
+///Most games kept at the same time, new games are refused beyond it
+const MAX_GAMES: usize = 10000;
+
+///Shared state of the server, the games are only kept in memory
+pub struct ServerState {
+    finals: Vec<String>,
+    acceptables: BTreeSet<String>,
+    config: Config,
+    games: Mutex<HashMap<Uuid, LiveGame>>,
+    stats: Mutex<Stats>,
+    game_ttl: Duration,
+    finished_game_ttl: Duration,
+    max_games: usize,
+}
+
+impl ServerState {
+    fn new(finals: Vec<String>, acceptables: BTreeSet<String>, config: Config) -> Self {
+        Self {
+            finals,
+            acceptables,
+            config,
+            games: Mutex::new(HashMap::new()),
+            stats: Mutex::new(Stats::new()),
+            game_ttl: GAME_TTL,
+            finished_game_ttl: FINISHED_GAME_TTL,
+            max_games: MAX_GAMES,
+        }
+    }
+
+    ///Drops the games nobody has asked for within their time to live, which is shorter once finished
+    fn evict_games(&self, games: &mut HashMap<Uuid, LiveGame>) {
+        games.retain(|_, live| {
+            let ttl = match game_state(&live.game) {
+                "continue" => self.game_ttl,
+                _ => self.finished_game_ttl,
+            };
+            live.last_active.elapsed() < ttl
+        });
+    }
+}
+
+///A game kept by the server, with the time of the last request for it
+struct LiveGame {
+    game: Game,
+    last_active: Instant,
+}
+
+///Body of POST /games/{id}/guess
+#[derive(Deserialize)]
+struct GuessBody {
+    guess: String,
+}
+
+///State of a game in the responses, the answer is only revealed when the game is over
+#[derive(Serialize)]
+struct GameResponse {
+    game_id: Uuid,
+    word_length: usize,
+    max_attempts: usize,
+    guesses: Vec<String>,
+    statuses: Vec<String>,
+    state: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    answer: Option<String>,
+}
+
+impl GameResponse {
+    fn new(game_id: Uuid, game: &Game) -> Self {
+        let state = game_state(game);
+        Self {
+            game_id,
+            word_length: game.word_length,
+            max_attempts: game.max_attempts,
+            guesses: game.guesses.clone(),
+            statuses: game
+                .guesses_status
+                .iter()
+                .map(|status| status.iter().collect())
+                .collect(),
+            state,
+            answer: match state {
+                "continue" => None,
+                _ => Some(game.answer.clone()),
+            },
+        }
+    }
+}
+
+///Decides the state of a game from its guesses
+fn game_state(game: &Game) -> &'static str {
+    if game.guesses.last() == Some(&game.answer) {
+        "won"
+    } else if game.guesses.len() == game.max_attempts {
+        "lost"
+    } else {
+        "continue"
+    }
+}
+
+///Makes an error response like {"error": "GAME_NOT_FOUND", "message": "..."}
+fn error_response(
+    mut response: actix_web::HttpResponseBuilder,
+    error: &str,
+    message: &str,
+) -> HttpResponse {
+    response.json(json!({ "error": error, "message": message }))
+}
+
+///Starts a new game with a random final word
+///The games out of time are dropped first, so that the games kept are bounded by max_games
+#[post("/games")]
+async fn post_games(state: web::Data<ServerState>) -> impl Responder {
+    let mut games = state.games.lock().unwrap();
+    state.evict_games(&mut games);
+    if games.len() >= state.max_games {
+        return error_response(
+            HttpResponse::ServiceUnavailable(),
+            "TOO_MANY_GAMES",
+            "Too many games in progress, try again later.",
+        );
+    }
+
+    let config = &state.config;
+    let game = match config.adversarial {
+        true => Game::new_adversarial(config.word_length(), config.max_attempts()),
+        false => Game::new(random_pick(&state.finals, None), config.max_attempts()),
+    };
+    let game_id = Uuid::new_v4();
+    let response = json!({
+        "game_id": game_id,
+        "word_length": game.word_length,
+        "max_attempts": game.max_attempts,
+    });
+    games.insert(
+        game_id,
+        LiveGame {
+            game,
+            last_active: Instant::now(),
+        },
+    );
+    HttpResponse::Created().json(response)
+}
+
+///Makes a guess in a game, the finished game is recorded in the session statistics
+#[post("/games/{id}/guess")]
+async fn post_games_guess(
+    path: web::Path<Uuid>,
+    body: web::Json<GuessBody>,
+    state: web::Data<ServerState>,
+) -> impl Responder {
+    let game_id = path.into_inner();
+    let mut games = state.games.lock().unwrap();
+    let game = match games.get_mut(&game_id) {
+        Some(live) => {
+            live.last_active = Instant::now();
+            &mut live.game
+        }
+        None => {
+            return error_response(
+                HttpResponse::NotFound(),
+                "GAME_NOT_FOUND",
+                &format!("Game {} not found.", game_id),
+            )
+        }
+    };
+    if game_state(game) != "continue" {
+        return error_response(
+            HttpResponse::Conflict(),
+            "GAME_FINISHED",
+            &format!("Game {} is already finished.", game_id),
+        );
+    }
+
+    match game.accept_guess(&body.guess, &state.acceptables, state.config.difficult) {
+        GameState::InvalidInput => {
+            return error_response(
+                HttpResponse::BadRequest(),
+                "INVALID_GUESS",
+                &format!("Guess '{}' is not accepted.", body.guess),
+            )
+        }
+        GameState::Won | GameState::Lost => {
+            state.stats.lock().unwrap().record(game.clone());
+        }
+        _ => (),
+    }
+
+    let mut response = serde_json::to_value(GameResponse::new(game_id, game)).unwrap();
+    response["status"] = json!(game
+        .guesses_status
+        .last()
+        .unwrap()
+        .iter()
+        .collect::<String>());
+    HttpResponse::Ok().json(response)
+}
+
+///Gets the current state of a game
+#[get("/games/{id}")]
+async fn get_games(path: web::Path<Uuid>, state: web::Data<ServerState>) -> impl Responder {
+    let game_id = path.into_inner();
+    match state.games.lock().unwrap().get_mut(&game_id) {
+        Some(live) => {
+            live.last_active = Instant::now();
+            HttpResponse::Ok().json(GameResponse::new(game_id, &live.game))
+        }
+        None => error_response(
+            HttpResponse::NotFound(),
+            "GAME_NOT_FOUND",
+            &format!("Game {} not found.", game_id),
+        ),
+    }
+}
+
+///Gets the statistics of the games finished since the server started
+#[get("/stats")]
+async fn get_stats(state: web::Data<ServerState>) -> impl Responder {
+    let stats = state.stats.lock().unwrap();
+    HttpResponse::Ok().json(json!({
+        "total_rounds": stats.total_rounds,
+        "success": stats.success,
+        "failure": stats.failure,
+        "average_attempts": stats.average_attempts(),
+        "current_streak": stats.current_streak,
+        "best_streak": stats.best_streak,
+        "most_frequent": stats.most_frequent(),
+    }))
+}
+
+///Serves the games over HTTP on 127.0.0.1:port until the process is stopped
+pub fn run_server(
+    port: u16,
+    finals: Vec<String>,
+    acceptables: BTreeSet<String>,
+    config: Config,
+) -> std::io::Result<()> {
+    let state = web::Data::new(ServerState::new(finals, acceptables, config));
+    actix_web::rt::System::new().block_on(
+        HttpServer::new(move || {
+            App::new()
+                .app_data(state.clone())
+                .service(post_games)
+                .service(post_games_guess)
+                .service(get_games)
+                .service(get_stats)
+        })
+        .bind(("127.0.0.1", port))?
+        .run(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test};
+    use clap::Parser;
+    use serde_json::Value;
+
+    ///Server state whose only final word is CRANE
+    fn test_state() -> ServerState {
+        ServerState::new(
+            vec!["CRANE".to_string()],
+            ["CRANE", "SLATE"]
+                .iter()
+                .map(|word| word.to_string())
+                .collect(),
+            Config::parse_from(["wordle"]),
+        )
+    }
+
+    macro_rules! test_app {
+        ($state:expr) => {
+            test::init_service(
+                App::new()
+                    .app_data(web::Data::new($state))
+                    .service(post_games)
+                    .service(post_games_guess)
+                    .service(get_games)
+                    .service(get_stats),
+            )
+            .await
+        };
+    }
+
+    ///Starts a game in the app, the status and the body of the response are returned
+    macro_rules! new_game {
+        ($app:expr) => {{
+            let response =
+                test::call_service($app, test::TestRequest::post().uri("/games").to_request())
+                    .await;
+            let status = response.status();
+            (status, test::read_body_json::<Value, _>(response).await)
+        }};
+    }
+
+    #[actix_web::test]
+    async fn test_create_guess_and_state() {
+        let app = test_app!(test_state());
+        let (status, game) = new_game!(&app);
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(game["word_length"], 5);
+        let game_id = game["game_id"].as_str().unwrap().to_string();
+
+        let guess = |word: &str| {
+            test::TestRequest::post()
+                .uri(&format!("/games/{}/guess", game_id))
+                .set_json(json!({ "guess": word }))
+                .to_request()
+        };
+        let response = test::call_service(&app, guess("ZZZZZ")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response: Value = test::call_and_read_body_json(&app, guess("slate")).await;
+        assert_eq!(response["status"], "RRGRG");
+        assert_eq!(response["state"], "continue");
+
+        let state: Value = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::get()
+                .uri(&format!("/games/{}", game_id))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(state["guesses"], json!(["SLATE"]));
+        assert!(state.get("answer").is_none());
+
+        let response: Value = test::call_and_read_body_json(&app, guess("CRANE")).await;
+        assert_eq!(response["state"], "won");
+        assert_eq!(response["answer"], "CRANE");
+        let response = test::call_service(&app, guess("CRANE")).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = test::call_service(
+            &app,
+            test::TestRequest::get()
+                .uri("/games/00000000-0000-0000-0000-000000000000")
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_too_many_games() {
+        let app = test_app!(ServerState {
+            max_games: 1,
+            ..test_state()
+        });
+        assert_eq!(new_game!(&app).0, StatusCode::CREATED);
+        let (status, error) = new_game!(&app);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(error["error"], "TOO_MANY_GAMES");
+    }
+
+    #[actix_web::test]
+    async fn test_games_evicted() {
+        //Unfinished games are kept, while finished ones are dropped at once
+        let app = test_app!(ServerState {
+            max_games: 1,
+            finished_game_ttl: Duration::ZERO,
+            ..test_state()
+        });
+        let (_, game) = new_game!(&app);
+        let game_id = game["game_id"].as_str().unwrap().to_string();
+        let response = test::call_service(
+            &app,
+            test::TestRequest::post()
+                .uri(&format!("/games/{}/guess", game_id))
+                .set_json(json!({ "guess": "CRANE" }))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(new_game!(&app).0, StatusCode::CREATED);
+        let response = test::call_service(
+            &app,
+            test::TestRequest::get()
+                .uri(&format!("/games/{}", game_id))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        //Games idle past their time to live are dropped as well
+        let app = test_app!(ServerState {
+            max_games: 1,
+            game_ttl: Duration::ZERO,
+            ..test_state()
+        });
+        assert_eq!(new_game!(&app).0, StatusCode::CREATED);
+        assert_eq!(new_game!(&app).0, StatusCode::CREATED);
+    }
+}
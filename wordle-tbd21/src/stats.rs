@@ -1,8 +1,115 @@
 use super::game::*;
+use chrono::{TimeZone, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+
+///Header of the CSV made by Stats::export_csv
+const CSV_HEADER: &str = "date,answer,guesses,result,attempts";
+
+///A game in the state of the official NYT Wordle
+///Unused rows of boardState are empty strings, lastPlayedTs is in milliseconds since the epoch
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NytGame {
+    board_state: Vec<String>,
+    game_status: String,
+    solution: String,
+    #[serde(default)]
+    last_played_ts: Option<i64>,
+}
+
+///The NYT state of a single game, or a list of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NytState {
+    Single(NytGame),
+    List(Vec<NytGame>),
+}
+
+///Statistics of the games sharing an answer
+///The attempts count every guess, including those of lost games
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WordStats {
+    pub times_answered: usize,
+    pub min_attempts: usize,
+    pub max_attempts: usize,
+    pub win_rate: f32,
+}
+
+///Settings of the GUI configuration window, kept in the state file across sessions
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct AppSettings {
+    #[serde(default)]
+    pub difficult: bool,
+
+    #[serde(default)]
+    pub stats_filename: String,
+
+    ///None for the default of the mode played
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<usize>,
+
+    ///None for the default length
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_length: Option<usize>,
+
+    #[serde(default)]
+    pub high_contrast: bool,
+}
+
+///Ids, names and descriptions of all achievements, in the order they are listed
+const ACHIEVEMENTS: [(&str, &str, &str); 6] = [
+    ("first_win", "First Win", "Win a game"),
+    ("win_streak_5", "On Fire", "Win 5 games in a row"),
+    ("solve_in_1", "Hole in One", "Win a game in 1 attempt"),
+    (
+        "solve_in_2",
+        "Sharp Eye",
+        "Win a game in 2 attempts or fewer",
+    ),
+    ("hundred_games", "Centurion", "Play 100 games"),
+    (
+        "perfect_week",
+        "Perfect Week",
+        "Win every game played on 7 days in a row",
+    ),
+];
+
+///An achievement, earned once its condition is met by the recorded games
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+
+    ///RFC 3339 time of the game which earned it, None if not earned yet
+    #[serde(default)]
+    pub earned_at: Option<String>,
+}
+
+impl Achievement {
+    ///Makes the list of all achievements, none of them earned
+    pub fn all() -> Vec<Self> {
+        ACHIEVEMENTS
+            .iter()
+            .map(|(id, name, description)| Self {
+                id: id.to_string(),
+                name: name.to_string(),
+                description: description.to_string(),
+                earned_at: None,
+            })
+            .collect()
+    }
+}
+
+fn no_achievement_earned(achievements: &[Achievement]) -> bool {
+    achievements
+        .iter()
+        .all(|achievement| achievement.earned_at.is_none())
+}
 
 ///Game statistics storage
+///total_rounds always equals the number of games, it is never read from JSON but counted again
 #[derive(Deserialize, Serialize)]
 pub struct Stats {
     #[serde(skip_deserializing, default)]
@@ -20,8 +127,25 @@ pub struct Stats {
     #[serde(skip, default)]
     pub success_attempts: usize,
 
+    ///Not stored in the state file, Stats::eval rebuilds it from the games when loading
     #[serde(skip, default)]
     pub word_counter: BTreeMap<String, i32>,
+
+    ///Number of games won in a row up to the last game
+    #[serde(skip, default)]
+    pub current_streak: usize,
+
+    ///Longest run of games won in a row
+    #[serde(skip, default)]
+    pub best_streak: usize,
+
+    ///Only stored in the state file once saved by the GUI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<AppSettings>,
+
+    ///Only stored in the state file once any of them is earned
+    #[serde(default, skip_serializing_if = "no_achievement_earned")]
+    pub achievements: Vec<Achievement>,
 }
 
 impl Stats {
@@ -34,13 +158,59 @@ impl Stats {
             failure: 0,
             success_attempts: 0,
             word_counter: BTreeMap::new(),
+            current_streak: 0,
+            best_streak: 0,
+            settings: None,
+            achievements: Achievement::all(),
         }
     }
 
+    ///Clears the games and everything counted from them, as if no game had been played
+    ///The GUI settings are kept, as they are not statistics
+    pub fn reset(&mut self) {
+        *self = Self {
+            settings: self.settings.take(),
+            ..Self::new()
+        };
+    }
+
     ///Makes a new Stats from JSON
+    ///Fails if the JSON is malformed or any game could not have been played
     pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let mut deserialized: Self = serde_json::from_str(json)?;
+        for (i, game) in deserialized.games.iter().enumerate() {
+            if game.guesses.len() > game.max_attempts {
+                return Err(
+                    format!("game {} has more than {} guesses", i + 1, game.max_attempts).into(),
+                );
+            }
+            if let Some(guess) = game
+                .guesses
+                .iter()
+                .find(|guess| guess.chars().count() != game.word_length)
+            {
+                return Err(format!("game {} has invalid guess '{}'", i + 1, guess).into());
+            }
+        }
+        //Achievements are listed in the state file as they were when saved, unknown ones are dropped
+        deserialized.achievements = Achievement::all()
+            .into_iter()
+            .map(|achievement| {
+                match deserialized
+                    .achievements
+                    .iter()
+                    .find(|saved| saved.id == achievement.id)
+                {
+                    Some(saved) => Achievement {
+                        earned_at: saved.earned_at.clone(),
+                        ..achievement
+                    },
+                    None => achievement,
+                }
+            })
+            .collect();
         deserialized.eval();
+        assert_eq!(deserialized.total_rounds, deserialized.games.len() as i32);
         Ok(deserialized)
     }
 
@@ -49,11 +219,149 @@ impl Stats {
         serde_json::to_string(self).unwrap()
     }
 
+    ///CSV serialization, one game per row after the header
+    ///Guesses are joined by semicolons, the date is empty if unknown
+    pub fn export_csv(&self) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        for game in &self.games {
+            csv += &format!(
+                "{},{},{},{},{}\n",
+                game.played_at
+                    .map(|date| date.format(date_time_format::FORMAT).to_string())
+                    .unwrap_or_default(),
+                game.answer,
+                game.guesses.join(";"),
+                if game.guesses.last() == Some(&game.answer) {
+                    "Win"
+                } else {
+                    "Loss"
+                },
+                game.guesses.len()
+            );
+        }
+        csv
+    }
+
+    ///Makes a new Stats from CSV made by Stats::export_csv
+    ///Fails if any row is malformed or contradicts itself
+    pub fn import_csv(csv: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut lines = csv.lines();
+        if lines.next().map(str::trim) != Some(CSV_HEADER) {
+            return Err("missing CSV header".into());
+        }
+
+        let mut stats = Self::new();
+        for (i, line) in lines
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+        {
+            let fields = line.trim().split(',').collect::<Vec<_>>();
+            let [date, answer, guesses, result, attempts] = fields[..] else {
+                return Err(format!("row {} does not have 5 fields", i + 1).into());
+            };
+            let guesses = guesses
+                .split(';')
+                .filter(|guess| !guess.is_empty())
+                .map(|guess| guess.to_ascii_uppercase())
+                .collect::<Vec<_>>();
+            if attempts.parse::<usize>()? != guesses.len() {
+                return Err(format!("row {} has wrong attempts", i + 1).into());
+            }
+
+            let mut game = Game::new(answer, guesses.len().max(super::DEFAULT_MAX_ATTEMPTS));
+            if answer.is_empty()
+                || guesses
+                    .iter()
+                    .any(|guess| guess.chars().count() != game.word_length)
+            {
+                return Err(format!("row {} has invalid guesses", i + 1).into());
+            }
+            game.guesses = guesses;
+            game.played_at = match date {
+                "" => None,
+                date => Some(date_time_format::parse(date)?),
+            };
+            let won = game.guesses.last() == Some(&game.answer);
+            match (result, won) {
+                ("Win", true) | ("Loss", false) => (),
+                _ => return Err(format!("row {} has wrong result", i + 1).into()),
+            }
+            stats.games.push(game);
+        }
+        stats.eval();
+        Ok(stats)
+    }
+
+    ///Makes a new Stats from the state of the official NYT Wordle, a single game or a list of them
+    ///Games still in progress are left out, fails if the result of any other game contradicts its guesses
+    pub fn import_from_nyt(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let nyt_games = match serde_json::from_str(json)? {
+            NytState::Single(game) => vec![game],
+            NytState::List(games) => games,
+        };
+
+        let mut stats = Self::new();
+        for (i, nyt_game) in nyt_games.into_iter().enumerate() {
+            if nyt_game.game_status == "IN_PROGRESS" {
+                continue;
+            }
+            let guesses = nyt_game
+                .board_state
+                .iter()
+                .filter(|guess| !guess.is_empty())
+                .map(|guess| guess.to_ascii_uppercase())
+                .collect::<Vec<_>>();
+
+            let mut game = Game::new(
+                &nyt_game.solution,
+                guesses.len().max(super::DEFAULT_MAX_ATTEMPTS),
+            );
+            if nyt_game.solution.is_empty()
+                || guesses
+                    .iter()
+                    .any(|guess| guess.chars().count() != game.word_length)
+            {
+                return Err(format!("game {} has invalid guesses", i + 1).into());
+            }
+            game.guesses = guesses;
+            game.played_at = match nyt_game.last_played_ts {
+                Some(ts) => Some(
+                    Utc.timestamp_millis_opt(ts)
+                        .single()
+                        .ok_or(format!("game {} has invalid lastPlayedTs", i + 1))?,
+                ),
+                None => None,
+            };
+            let won = game.guesses.last() == Some(&game.answer);
+            match (nyt_game.game_status.as_str(), won) {
+                ("WIN", true) | ("FAIL", false) => (),
+                _ => return Err(format!("game {} has wrong gameStatus", i + 1).into()),
+            }
+            stats.games.push(game);
+        }
+        stats.eval();
+        Ok(stats)
+    }
+
+    ///Adds the games of another Stats, keeping all the games in the order they were played
+    ///Games of unknown dates come first, as they were saved before the dates were recorded
+    pub fn merge(&mut self, other: Stats) {
+        self.games.extend(other.games);
+        self.games.sort_by_key(|game| game.played_at);
+        self.eval();
+    }
+
     ///Scans self.games to evaluate other fields
+    ///The fields are recomputed from scratch so that evaluating again does not count the games twice
     pub fn eval(&mut self) {
+        self.total_rounds = 0;
+        self.success = 0;
+        self.failure = 0;
+        self.success_attempts = 0;
+        self.word_counter.clear();
         for game in &self.games {
             self.total_rounds += 1;
-            if game.guesses.len() == 6 && (game.guesses[5] != game.answer) {
+            if game.guesses.last() != Some(&game.answer) {
                 self.failure += 1;
             } else {
                 self.success += 1;
@@ -66,12 +374,16 @@ impl Stats {
                     .or_insert(1);
             }
         }
+        (self.current_streak, self.best_streak) = self.streak();
     }
 
-    ///Accepts result from a game
-    pub fn record(&mut self, game: Game) {
+    ///Accepts result from a game, which is stamped with the time it ends
+    ///Returns: Vec<Achievement> -- the achievements earned by the game
+    pub fn record(&mut self, mut game: Game) -> Vec<Achievement> {
+        let now = Utc::now();
+        game.played_at = Some(now);
         self.total_rounds += 1;
-        if game.guesses.len() == 6 && (game.guesses[5] != game.answer) {
+        if game.guesses.last() != Some(&game.answer) {
             self.failure += 1;
         } else {
             self.success += 1;
@@ -84,6 +396,120 @@ impl Stats {
                 .or_insert(1);
         }
         self.games.push(game);
+        assert_eq!(self.total_rounds, self.games.len() as i32);
+        (self.current_streak, self.best_streak) = self.streak();
+
+        let mut earned = vec![];
+        for i in 0..self.achievements.len() {
+            if self.achievements[i].earned_at.is_none() && self.achieved(&self.achievements[i].id) {
+                self.achievements[i].earned_at = Some(now.to_rfc3339());
+                earned.push(self.achievements[i].clone());
+            }
+        }
+        earned
+    }
+
+    ///Checks the condition of the achievement against the games
+    fn achieved(&self, id: &str) -> bool {
+        let won_in = |attempts: usize| {
+            self.games.iter().any(|game| {
+                game.guesses.last() == Some(&game.answer) && game.guesses.len() <= attempts
+            })
+        };
+        match id {
+            "first_win" => self.success > 0,
+            "win_streak_5" => self.best_streak >= 5,
+            "solve_in_1" => won_in(1),
+            "solve_in_2" => won_in(2),
+            "hundred_games" => self.games.len() >= 100,
+            "perfect_week" => self.perfect_days() >= 7,
+            _ => false,
+        }
+    }
+
+    ///Counts the longest run of days in a row on which every game played was won
+    ///Days are in UTC, games of unknown dates are left out
+    fn perfect_days(&self) -> usize {
+        let mut days: BTreeMap<chrono::NaiveDate, bool> = BTreeMap::new();
+        for game in &self.games {
+            if let Some(played_at) = game.played_at {
+                let won = game.guesses.last() == Some(&game.answer);
+                *days.entry(played_at.date_naive()).or_insert(true) &= won;
+            }
+        }
+
+        let mut running = 0;
+        let mut best = 0;
+        let mut previous: Option<chrono::NaiveDate> = None;
+        for (day, perfect) in days {
+            if !perfect {
+                running = 0;
+            } else if previous.and_then(|previous| previous.succ_opt()) == Some(day) {
+                running += 1;
+            } else {
+                running = 1;
+            }
+            best = best.max(running);
+            previous = Some(day);
+        }
+        best
+    }
+
+    ///Counts the games won in each number of attempts
+    ///Returns: Vec<usize> -- the number of games won in i + 1 attempts at index i,
+    ///as long as the most attempts allowed in any game, at least 6
+    pub fn guess_distribution(&self) -> Vec<usize> {
+        let attempts = self
+            .games
+            .iter()
+            .map(|game| game.max_attempts)
+            .fold(super::DEFAULT_MAX_ATTEMPTS, usize::max);
+        let mut distribution = vec![0; attempts];
+        for game in &self.games {
+            if game.guesses.last() == Some(&game.answer) {
+                distribution[game.guesses.len() - 1] += 1;
+            }
+        }
+        distribution
+    }
+
+    ///Scans self.games in order for the win streaks, a lost game ends a streak
+    ///Returns: (usize, usize) -- the current streak and the best streak
+    pub fn streak(&self) -> (usize, usize) {
+        let mut running = 0;
+        let mut best = 0;
+        for game in &self.games {
+            if game.guesses.last() == Some(&game.answer) {
+                running += 1;
+                best = best.max(running);
+            } else {
+                running = 0;
+            }
+        }
+        let current = self
+            .games
+            .iter()
+            .rev()
+            .take_while(|game| game.guesses.last() == Some(&game.answer))
+            .count();
+        (current, best)
+    }
+
+    ///Finds the game won in the fewest attempts, the earliest one if several are
+    pub fn best_game(&self) -> Option<&Game> {
+        self.games
+            .iter()
+            .filter(|game| game.guesses.last() == Some(&game.answer))
+            .min_by_key(|game| game.guesses.len())
+    }
+
+    ///Finds the game won in the fewest attempts among those answered by the word, see best_game
+    pub fn best_game_for_word(&self, word: &str) -> Option<&Game> {
+        let word = word.to_ascii_uppercase();
+        self.games
+            .iter()
+            .filter(|game| game.answer == word && game.guesses.last() == Some(&game.answer))
+            .min_by_key(|game| game.guesses.len())
     }
 
     ///Calculates the player's average attempts to win a game
@@ -96,13 +522,85 @@ impl Stats {
     }
 
     ///Returns the top 5 words that the player tried most frequently
+    ///Words tried equally often are in alphabetical order
     pub fn most_frequent(&self) -> Vec<(&String, &i32)> {
         let mut vec: Vec<_> = self.word_counter.iter().collect();
-        vec.sort_by(|&(_, a), &(_, b)| b.cmp(a));
+        vec.sort_by(|&(ka, a), &(kb, b)| b.cmp(a).then(ka.cmp(kb)));
         if vec.len() > 5 {
             vec[..5].to_vec()
         } else {
             vec
         }
     }
+
+    ///Counts the letters in each position of the answers and the guesses of all games
+    pub fn aggregate_letter_frequencies(
+        &self,
+    ) -> BTreeMap<char, [usize; super::DEFAULT_WORD_LENGTH]> {
+        let words = self
+            .games
+            .iter()
+            .flat_map(|game| std::iter::once(&game.answer).chain(&game.guesses))
+            .cloned()
+            .collect::<Vec<_>>();
+        Game::letter_frequencies(&words)
+    }
+
+    ///Returns the 3 letters found most often in the same position, with the position from 1 and the count
+    ///Letters found equally often are in alphabetical order, and then by position
+    pub fn top_letter_positions(&self) -> Vec<(char, usize, usize)> {
+        let mut vec = self
+            .aggregate_letter_frequencies()
+            .into_iter()
+            .flat_map(|(letter, counts)| {
+                counts
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(j, n)| (letter, j + 1, n))
+            })
+            .filter(|(_, _, n)| *n > 0)
+            .collect::<Vec<_>>();
+        vec.sort_by(|(la, pa, a), (lb, pb, b)| b.cmp(a).then(la.cmp(lb)).then(pa.cmp(pb)));
+        vec.truncate(3);
+        vec
+    }
+
+    ///Groups self.games by their answers
+    ///Returns: HashMap<String, WordStats> -- the statistics of each word which has been the answer
+    pub fn per_word_stats(&self) -> HashMap<String, WordStats> {
+        let mut wins: HashMap<&String, usize> = HashMap::new();
+        let mut per_word: HashMap<String, WordStats> = HashMap::new();
+        for game in &self.games {
+            let attempts = game.guesses.len();
+            let stats = per_word.entry(game.answer.clone()).or_insert(WordStats {
+                min_attempts: attempts,
+                ..Default::default()
+            });
+            stats.times_answered += 1;
+            stats.min_attempts = stats.min_attempts.min(attempts);
+            stats.max_attempts = stats.max_attempts.max(attempts);
+            if game.guesses.last() == Some(&game.answer) {
+                *wins.entry(&game.answer).or_insert(0) += 1;
+            }
+        }
+        for (word, stats) in per_word.iter_mut() {
+            stats.win_rate =
+                wins.get(word).copied().unwrap_or(0) as f32 / stats.times_answered as f32;
+        }
+        per_word
+    }
+
+    ///Returns the n answers with the lowest win rates
+    ///Answers with the same win rate are ordered by the most attempts, and then alphabetically
+    pub fn hardest_answers(&self, n: usize) -> Vec<(String, WordStats)> {
+        let mut vec = self.per_word_stats().into_iter().collect::<Vec<_>>();
+        vec.sort_by(|(ka, a), (kb, b)| {
+            a.win_rate
+                .total_cmp(&b.win_rate)
+                .then(b.max_attempts.cmp(&a.max_attempts))
+                .then(ka.cmp(kb))
+        });
+        vec.truncate(n);
+        vec
+    }
 }
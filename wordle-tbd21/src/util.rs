@@ -1,8 +1,10 @@
 use super::*;
 use colored::Colorize;
-use eframe::egui;
 use rand::seq::SliceRandom;
-use std::io;
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
 
 ///The tool function for adding 's' to plural words
 pub fn make_plural(n: i32) -> &'static str {
@@ -13,19 +15,9 @@ pub fn make_plural(n: i32) -> &'static str {
     }
 }
 
-///The tool function for colorizing characters according to their status
-pub fn colorize_gui(status: char) -> egui::Color32 {
-    match status {
-        'R' => egui::Color32::LIGHT_RED,
-        'Y' => egui::Color32::YELLOW,
-        'G' => egui::Color32::LIGHT_GREEN,
-        _ => egui::Color32::WHITE,
-    }
-}
-
 ///The tool function for colorizing characters according to their status
 ///Arguments: status: char -- status indicator, ch: char -- the character to colorize
-/// Returns: String -- colorized character
+/// Returns: String -- colorized character, or the plain character if colors are disabled by `--no-color` or NO_COLOR
 pub fn colorize_tty(status: char, ch: char) -> String {
     match status {
         'G' => format!("{}", String::from(ch).green().bold()),
@@ -35,19 +27,69 @@ pub fn colorize_tty(status: char, ch: char) -> String {
     }
 }
 
+///The tool function for colorizing characters in the colorblind mode, blue and orange take the place of green and yellow
+///Arguments: status: char -- status indicator, ch: char -- the character to colorize
+/// Returns: String -- colorized character, or the plain character if colors are disabled by `--no-color` or NO_COLOR
+pub fn colorize_tty_colorblind(status: char, ch: char) -> String {
+    match status {
+        'G' => format!("{}", String::from(ch).truecolor(0, 114, 178).bold()),
+        'Y' => format!("{}", String::from(ch).truecolor(230, 159, 0).bold()),
+        'R' => format!("{}", String::from(ch).truecolor(96, 96, 96).bold()),
+        _ => String::from(ch),
+    }
+}
+
+///The tool function for turning a letter status into an emoji of the share grid
+///Arguments: status: char -- status indicator
+/// Returns: &str -- green square for 'G', yellow square for 'Y', black square otherwise
+pub fn emoji_status(status: char) -> &'static str {
+    match status {
+        'G' => "🟩",
+        'Y' => "🟨",
+        _ => "⬛",
+    }
+}
+
 ///Picks word randomly for GUI mode
-pub fn random_pick(finals: &Vec<String>) -> &str {
-    finals.choose(&mut rand::thread_rng()).unwrap()
+///Re-rolls if the previous answer is picked again, unless it is the only final word
+///Exits if there is no final word to pick
+pub fn random_pick<'a>(finals: &'a Vec<String>, previous: Option<&str>) -> &'a str {
+    let mut rng = rand::thread_rng();
+    let can_change = finals.iter().any(|word| Some(word.as_str()) != previous);
+    loop {
+        let word = finals.choose(&mut rng).unwrap_or_else(|| {
+            eprintln!("Final word set is empty.");
+            std::process::exit(1);
+        });
+        if !can_change || Some(word.as_str()) != previous {
+            break word;
+        }
+    }
 }
 
 ///The tool function for printing error information when the arguments are invalid and exiting with a non-zero value
-pub fn invalid_arguments(is_tty: bool) {
+///Arguments: is_tty: bool -- whether in TTY mode, message: &str -- the specific reason of the error
+pub fn invalid_arguments(is_tty: bool, message: &str) {
     if is_tty {
-        println!("{}", "Invalid arguments".red().bold());
+        println!("{}: {}", "Invalid arguments".red().bold(), message);
+    } else {
+        //Keeps the standard output clean for non-TTY mode
+        eprintln!("Invalid arguments: {}", message);
     }
     std::process::exit(1);
 }
 
+///The tool function for printing a warning without exiting
+///Arguments: is_tty: bool -- whether in TTY mode, message: &str -- the content of the warning
+pub fn warning(is_tty: bool, message: &str) {
+    if is_tty {
+        println!("{}: {}", "Warning".bright_yellow().bold(), message);
+    } else {
+        //Keeps the standard output clean for non-TTY mode
+        eprintln!("Warning: {}", message);
+    }
+}
+
 ///The tool function for printing error information when the player's input are invalid
 pub fn invalid_input(is_tty: bool) {
     if is_tty {
@@ -57,27 +99,246 @@ pub fn invalid_input(is_tty: bool) {
     }
 }
 
+///Lines of the input read by a background thread, so that waiting for one can time out
+pub struct TimedInput {
+    lines: mpsc::Receiver<String>,
+}
+
+impl TimedInput {
+    ///Starts reading the lines of the reader in the background
+    ///Nothing else may read from the same input afterwards, as the thread reads ahead
+    pub fn spawn<R: BufRead + Send + 'static>(reader: R) -> Self {
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else { break };
+                //Nobody waits for the lines any more
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { lines }
+    }
+
+    ///Waits for the next line as long as it takes
+    ///An ended input gives empty lines, the same as io::Stdin::read_line
+    pub fn read_line(&self) -> String {
+        self.lines.recv().unwrap_or_default()
+    }
+
+    ///Waits for the next line until the time runs out
+    ///In TTY mode the seconds left are shown on a line overwritten every second
+    ///Returns: Option<String> -- the line, None if the time runs out
+    pub fn read_line_timeout(&self, timeout: Duration, is_tty: bool) -> Option<String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if is_tty {
+                let seconds = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+                print!(
+                    "\r{} second{} left: ",
+                    seconds.to_string().bold(),
+                    make_plural(seconds as i32)
+                );
+                io::stdout().flush().ok();
+            }
+            if left.is_zero() {
+                if is_tty {
+                    println!();
+                }
+                return None;
+            }
+            match self.lines.recv_timeout(left.min(Duration::from_secs(1))) {
+                Ok(line) => return Some(line),
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => return Some(String::new()),
+            }
+        }
+    }
+}
+
+///Reads a line of the standard input, through the timed input if there is one
+pub fn read_input_line(timed_input: Option<&TimedInput>) -> String {
+    match timed_input {
+        Some(input) => input.read_line(),
+        None => {
+            let mut line = String::new();
+            io::stdin()
+                .read_line(&mut line)
+                .expect(&format!("{}", "IO failure".red().bold()));
+            line
+        }
+    }
+}
+
 ///Asks the player whether to play another time
-pub fn want_to_continue() -> bool {
-    let mut choice = String::new();
-    io::stdin()
-        .read_line(&mut choice)
-        .expect(&format!("{}", "IO failure".red().bold()));
+pub fn want_to_continue(timed_input: Option<&TimedInput>) -> bool {
+    let choice = read_input_line(timed_input);
     match choice.trim().to_ascii_uppercase().as_str() {
         "Y" => true,
         _ => false,
     }
 }
 
+///Prints the words in alphabetical order, one per line, followed by their count
+///Arguments: words: impl IntoIterator<Item = &String> -- the words to print, duplicates are printed once
+pub fn list_words<'a>(words: impl IntoIterator<Item = &'a String>) {
+    let words = words.into_iter().collect::<BTreeSet<_>>();
+    for word in &words {
+        println!("{}", word);
+    }
+    println!(
+        "# Total: {} word{}",
+        words.len(),
+        make_plural(words.len() as i32)
+    );
+}
+
+///Prints statistics of the word lists for curators of custom lists
+///One statistic per line so that the output is easy to parse
+///The duplicates are the words listed more than once in each file, which are only kept once
+pub fn analyze_wordlist(
+    finals: &[String],
+    acceptables: &BTreeSet<String>,
+    final_duplicates: usize,
+    acceptable_duplicates: usize,
+) {
+    println!("FINALS {}", finals.len());
+    println!("ACCEPTABLES {}", acceptables.len());
+
+    //Length distribution of the final words
+    let mut lengths = BTreeMap::new();
+    for word in finals {
+        *lengths.entry(word.len()).or_insert(0) += 1;
+    }
+    for (length, n) in lengths {
+        println!("LENGTH {} {}", length, n);
+    }
+
+    //Occurrences of each letter in the final words
+    for letter in LETTERS {
+        let n: usize = finals
+            .iter()
+            .map(|word| word.chars().filter(|c| *c == letter).count())
+            .sum();
+        println!("LETTER {} {}", letter, n);
+    }
+
+    //Words listed more than once in each file
+    println!("DUPLICATES FINALS {}", final_duplicates);
+    println!("DUPLICATES ACCEPTABLES {}", acceptable_duplicates);
+}
+
+///Plays a game against the answer with the best guesses, without user input
+///In difficult mode only the words consistent with the previous guesses are guessed
+///Arguments: answer: &str -- the word to guess, finals: &BTreeSet<String> -- the possible answers, acceptables: &BTreeSet<String> -- the words allowed to guess, max_attempts: usize -- the number of attempts allowed, is_difficult: bool -- whether in difficult mode
+/// Returns: Game -- the finished game
+pub fn auto_play(
+    answer: &str,
+    finals: &BTreeSet<String>,
+    acceptables: &BTreeSet<String>,
+    max_attempts: usize,
+    is_difficult: bool,
+) -> game::Game {
+    let mut game = game::Game::new(answer, max_attempts);
+    loop {
+        let candidates = game.possible_words(finals);
+        let consistent_words;
+        let all_words = if is_difficult && !game.guesses.is_empty() {
+            consistent_words = candidates.iter().cloned().collect();
+            &consistent_words
+        } else {
+            acceptables
+        };
+        let guess = game
+            .best_next_guess(&candidates, all_words)
+            .expect("the answer should be a candidate");
+        match game.accept_guess(&guess, acceptables, is_difficult) {
+            game::GameState::Continue => {}
+            _ => break game,
+        }
+    }
+}
+
+///Prints each guess of the solver and its result as in non-TTY mode, followed by the outcome
+///Arguments: answer: &str -- the word to guess, the others are the same as auto_play
+pub fn solve(
+    answer: &str,
+    finals: &BTreeSet<String>,
+    acceptables: &BTreeSet<String>,
+    max_attempts: usize,
+    is_difficult: bool,
+) {
+    let game = auto_play(answer, finals, acceptables, max_attempts, is_difficult);
+    for (guess, status) in game.guesses.iter().zip(&game.guesses_status) {
+        println!("{} {}", guess, status.iter().collect::<String>());
+    }
+    if game.guesses.last() == Some(&game.answer) {
+        println!("CORRECT {}", game.guesses.len());
+    } else {
+        println!("FAILED {}", game.answer);
+    }
+}
+
+///Runs the solver against every final word and prints the number of guesses needed
+///The games are not limited by max_attempts, a word needing more guesses is counted as failed
+///Arguments: the same as auto_play, except the answer
+/// Returns: bool -- whether every word is solved within max_attempts
+pub fn benchmark_solver(
+    finals: &BTreeSet<String>,
+    acceptables: &BTreeSet<String>,
+    max_attempts: usize,
+    is_difficult: bool,
+) -> bool {
+    let attempts = finals
+        .iter()
+        .map(|answer| {
+            auto_play(answer, finals, acceptables, usize::MAX, is_difficult)
+                .guesses
+                .len()
+        })
+        .collect::<Vec<_>>();
+    let failed = attempts.iter().filter(|n| **n > max_attempts).count();
+    println!("WORDS {}", attempts.len());
+    println!(
+        "AVERAGE {:.3}",
+        attempts.iter().sum::<usize>() as f64 / attempts.len().max(1) as f64
+    );
+    println!("MAX {}", attempts.iter().max().unwrap_or(&0));
+    println!("FAILED {}", failed);
+    failed == 0
+}
+
 ///Picks word according to the given configuration for non-GUI mode
-pub fn pick_word(config: &mut Config, finals: &Vec<String>, day: usize) -> String {
+///The answer is read through the timed input if there is one, as it owns the standard input
+pub fn pick_word(
+    config: &mut Config,
+    finals: &Vec<String>,
+    day: usize,
+    timed_input: Option<&TimedInput>,
+) -> String {
+    //The arguments should not conflict with each other, whether the answer is given or not
+    if !config.random {
+        if config.day.is_some() {
+            invalid_arguments(config.is_tty, "`--day` requires `--random`");
+        }
+        if config.seed.is_some() {
+            invalid_arguments(config.is_tty, "`--seed` requires `--random`");
+        }
+    }
+
+    //Playing once more moves on to the next day, which may run out of final words
+    if config.random && day > finals.len() {
+        invalid_arguments(
+            config.is_tty,
+            &format!("day {} exceeds final word count {}", day, finals.len()),
+        );
+    }
+
     match config.random {
         true => finals[day - 1].to_string(),
         false => {
-            //The arguments should not conflict with each other
-            if config.day.is_some() || config.seed.is_some() {
-                invalid_arguments(config.is_tty);
-            }
             match config.word {
                 Some(ref mut word) => {
                     let result = word.clone();
@@ -86,16 +347,11 @@ pub fn pick_word(config: &mut Config, finals: &Vec<String>, day: usize) -> Strin
                 }
                 //Reads word from player's input until the input is valid
                 None => loop {
-                    let mut word = String::new();
-
                     if config.is_tty {
                         println!("Please enter the answer: ");
                     }
 
-                    io::stdin()
-                        .read_line(&mut word)
-                        .expect(&format!("{}", "IO failure".red().bold()));
-                    word = word.trim().to_string();
+                    let word = read_input_line(timed_input).trim().to_string();
 
                     if finals.contains(&word.to_ascii_uppercase()) {
                         break word;
@@ -65,8 +65,8 @@ fn test_07_5_pts_save_game_state() {
     TestCase::read("07_01_save_state").run_and_compare_game_state();
     // load game state, check statistics and JSON output after several rounds
     TestCase::read("07_02_load_state").run_and_compare_game_state();
-    // load game state from an invalid JSON
-    TestCase::read("07_03_invalid_json_format").run_and_expect_exit();
+    // load game state from an invalid JSON, which is ignored with a warning
+    TestCase::read("07_03_invalid_json_format").run_and_compare_game_state();
 }
 
 #[test]
@@ -116,7 +116,43 @@ impl TestCase {
     }
 
     pub fn run_and_compare_result(&self) {
-        let mut command = self.execute_program_and_feed_input();
+        let command = self.execute_program_and_feed_input();
+        self.wait_and_compare_result(command);
+    }
+
+    /// Feeds the input in parts separated by blank lines, pausing before each part after the first.
+    /// The input stays open in between, so that the program can time out while waiting for it.
+    #[allow(dead_code)]
+    pub fn run_and_compare_result_with_pauses(&self, pause: std::time::Duration) {
+        let mut command = Command::new(EXE_PATH.as_os_str())
+            .args(&self.arguments)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to execute process");
+
+        let mut stdin = command.stdin.take().unwrap();
+        let parts = self
+            .input
+            .split("\n\n")
+            .map(|part| format!("{}\n", part.trim_end()))
+            .collect::<Vec<_>>();
+        let feeder = std::thread::spawn(move || {
+            for (i, part) in parts.iter().enumerate() {
+                if i > 0 {
+                    std::thread::sleep(pause);
+                }
+                // the program may have exited already
+                if stdin.write_all(part.as_bytes()).is_err() {
+                    break;
+                }
+            }
+        });
+        self.wait_and_compare_result(command);
+        feeder.join().unwrap();
+    }
+
+    fn wait_and_compare_result(&self, mut command: Child) {
         // read stdout from user program
         let mut output = Vec::new();
         command
@@ -161,24 +197,139 @@ impl TestCase {
         self.run_and_compare_result();
 
         // load state and compare with answer
-        let run_state: serde_json::Value =
+        let mut run_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&run_state_file).unwrap())).unwrap();
         let answer_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&after_state_file).unwrap()))
                 .unwrap();
+
+        // games played in this run are stamped with the current time
+        // so the time is only compared if the answer gives one
+        let answer_games = answer_state["games"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        if let Some(games) = run_state["games"].as_array_mut() {
+            for (game, answer_game) in games.iter_mut().zip(&answer_games) {
+                if answer_game.get("played_at").is_none() {
+                    game.as_object_mut().unwrap().remove("played_at");
+                }
+            }
+        }
+        // achievements earned in this run are stamped with the current time too
+        // so the answer leaves out the time of those only checked to be earned
+        let answer_achievements = answer_state["achievements"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        if let Some(achievements) = run_state
+            .get_mut("achievements")
+            .and_then(|achievements| achievements.as_array_mut())
+        {
+            for (achievement, answer_achievement) in
+                achievements.iter_mut().zip(&answer_achievements)
+            {
+                if answer_achievement.get("earned_at").is_none() {
+                    assert!(
+                        achievement["earned_at"].is_string(),
+                        "case {} incorrect: achievement {} not earned",
+                        self.name,
+                        achievement["id"]
+                    );
+                    achievement.as_object_mut().unwrap().remove("earned_at");
+                }
+            }
+        }
         assert_json_eq!(run_state, answer_state);
     }
 
+    #[allow(dead_code)]
+    pub fn run_and_compare_csv(&mut self) {
+        // import games before & export games at the end
+        let case_dir = Path::new("tests").join("cases");
+        let before_csv_file = case_dir.join(format!("{}.before.csv", self.name));
+        let run_csv_file = case_dir.join(format!("{}.run.csv", self.name));
+        let after_csv_file = case_dir.join(format!("{}.after.csv", self.name));
+
+        self.arguments.append(&mut vec![
+            "--import-csv".to_string(),
+            before_csv_file.to_str().unwrap().to_string(),
+            "--export-csv".to_string(),
+            run_csv_file.to_str().unwrap().to_string(),
+        ]);
+        self.run_and_compare_result();
+
+        // compare exported games with answer
+        // the date is only compared if the answer gives one, like in run_and_compare_game_state
+        let run_csv = std::fs::read_to_string(&run_csv_file).unwrap();
+        let after_csv = std::fs::read_to_string(&after_csv_file).unwrap();
+        assert_eq!(run_csv.lines().count(), after_csv.lines().count());
+        for (run_line, after_line) in run_csv.lines().zip(after_csv.lines()) {
+            let (run_date, run_rest) = run_line.split_once(',').unwrap();
+            let (after_date, after_rest) = after_line.split_once(',').unwrap();
+            assert!(
+                after_date.is_empty() || run_date == after_date,
+                "case {} incorrect: wrong date in '{}'",
+                self.name,
+                run_line
+            );
+            assert_eq!(run_rest, after_rest, "case {} incorrect", self.name);
+        }
+    }
+
+    /// Runs the case in a pseudo terminal made by `script` from util-linux, as the TTY mode is not used otherwise.
+    /// Returns the output with its escape codes, or None if `script` is not available.
+    #[allow(dead_code)]
+    pub fn run_in_tty(&self) -> Option<String> {
+        let in_file = Path::new("tests")
+            .join("cases")
+            .join(format!("{}.in", self.name));
+        let command = format!(
+            "{} {} < {}",
+            EXE_PATH.display(),
+            self.arguments.join(" "),
+            in_file.display()
+        );
+        let output = Command::new("script")
+            .args(["-qec", &command, "/dev/null"])
+            .env_remove("NO_COLOR")
+            .output()
+            .ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Starts the case as a server, which is killed when the returned handle is dropped.
+    /// Waits until the server accepts connections on the given port.
+    #[allow(dead_code)]
+    pub fn run_server(&self, port: u16) -> ServerHandle {
+        let handle = ServerHandle(self.execute_program_and_feed_input());
+        while std::net::TcpStream::connect(("127.0.0.1", port)).is_err() {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        handle
+    }
+
     pub fn run_and_expect_exit(&self) {
         let command = self.execute_program_and_feed_input();
+        let status = command
+            .wait_with_output()
+            .expect("failed to wait on process")
+            .status;
+        // an error is reported with a nonzero exit code, while a panic would exit with 101
         assert!(
-            !command
-                .wait_with_output()
-                .expect("failed to wait on process")
-                .status
-                .success(),
-            "case {} should exit with error",
+            !status.success() && status.code() != Some(101),
+            "case {} should exit with error, not succeed or panic",
             self.name
         );
     }
 }
+
+/// A running server started by `TestCase::run_server`
+pub struct ServerHandle(Child);
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
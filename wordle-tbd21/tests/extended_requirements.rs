@@ -0,0 +1,614 @@
+use ntest::timeout;
+
+mod common;
+use common::TestCase;
+
+#[test]
+#[timeout(2000)]
+fn test_ext_01_difficult_mode_repeated_letters() {
+    // a guess revealing two E's must be followed by guesses with at least two E's
+    TestCase::read("ext_01_difficult_repeated_letters").run_and_compare_result();
+    // both green E's must stay in place, not only the first one
+    TestCase::read("ext_02_difficult_repeated_greens").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_02_stats_round_trip() {
+    // load the state saved by a previous run, play one more game and save again
+    // check that the loaded games are counted only once
+    TestCase::read("ext_03_stats_round_trip").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_03_word_counter_from_state() {
+    // the most frequently used words are not stored in the state file
+    // check that they are rebuilt from the loaded games
+    TestCase::read("ext_04_word_counter_from_state").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_04_analyze_wordlist() {
+    // print the statistics of the given word lists without starting a game
+    // duplicated lines in the final set are counted only once
+    TestCase::read("ext_05_analyze_wordlist").run_and_compare_result();
+    // words listed more than once are reported for each list, whatever their case
+    TestCase::read("ext_91_analyze_duplicates").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_05_invalid_state() {
+    // a truncated state file is ignored instead of crashing the game
+    TestCase::read("ext_06_malformed_state").run_and_compare_game_state();
+    // the truncated file is kept aside instead of being overwritten
+    let backup = "tests/cases/ext_06_malformed_state.run.json.bak";
+    assert_eq!(
+        std::fs::read_to_string(backup).unwrap(),
+        std::fs::read_to_string("tests/cases/ext_06_malformed_state.before.json").unwrap()
+    );
+    std::fs::remove_file(backup).unwrap();
+    // so is a state file holding a game which could not have been played
+    TestCase::read("ext_07_invalid_guess_state").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_06_max_attempts() {
+    // a game of 4 attempts is lost after the fourth wrong guess
+    TestCase::read("ext_08_max_attempts_4").run_and_compare_result();
+    // a game of 8 attempts can still be won at the seventh guess
+    // check that the number of attempts is saved with the game
+    TestCase::read("ext_09_max_attempts_8").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_07_word_length() {
+    // a complete game of 4-letter words, 5-letter guesses are rejected
+    TestCase::read("ext_10_word_length_4").run_and_compare_result();
+    // words longer than 10 letters are not supported
+    TestCase::read("ext_11_invalid_word_length").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_08_difficult_mode_previous_guess() {
+    // two yellow E's in the previous guess must both be reused, as in the official hard mode
+    // a yellow letter may stay in the same place
+    TestCase::read("ext_12_difficult_doubled_yellow").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_09_no_color() {
+    // without a TTY the output has no colors, so `--no-color` must not change it
+    let case = TestCase::read("ext_13_no_color");
+    case.run_and_compare_result();
+    let colored_case = TestCase::read("ext_90_color");
+    colored_case.run_and_compare_result();
+
+    // check in TTY mode that `--no-color` removes the escape codes which are printed without it
+    #[cfg(target_os = "linux")]
+    if let (Some(output), Some(colored_output)) = (case.run_in_tty(), colored_case.run_in_tty()) {
+        assert!(
+            !output.contains('\x1b'),
+            "case ext_13_no_color incorrect: escape codes in TTY output"
+        );
+        assert!(
+            colored_output.contains("\x1b["),
+            "case ext_90_color incorrect: no escape codes in TTY output"
+        );
+    }
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_10_share() {
+    // the emoji grid follows the result, one row for each guess
+    TestCase::read("ext_14_share_won").run_and_compare_result();
+    // a lost game is shared as X attempts
+    TestCase::read("ext_15_share_lost").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_11_possible_words() {
+    // count the acceptable words consistent with the guesses so far in a small word list
+    TestCase::read("ext_16_candidates_word_list").run_and_compare_result();
+    // a repeated letter is marked red only if the answer has no more of it
+    TestCase::read("ext_17_candidates_repeated").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_12_hint() {
+    // CRANE and SLATE split the final words best, words with rare letters are never suggested
+    // once the answer is the only word left, it is the first suggestion
+    TestCase::read("ext_18_hint").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_13_list_words() {
+    // print the loaded final words in alphabetical order without starting a game
+    TestCase::read("ext_19_list_words").run_and_compare_result();
+    // the same for the acceptable words
+    TestCase::read("ext_20_list_acceptable").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_14_most_frequent_ties() {
+    // six words are tried once each, only five of them are listed
+    // check that words tried equally often are listed in alphabetical order
+    TestCase::read("ext_21_most_frequent_ties").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_15_csv_round_trip() {
+    // import the games from CSV, play one more game and export them again
+    // check that the imported games are kept as they were, dates included
+    TestCase::read("ext_22_csv_round_trip").run_and_compare_csv();
+    // a row whose result contradicts its guesses is refused
+    TestCase::read("ext_23_invalid_csv").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_16_played_at() {
+    // a loaded time is saved again in UTC, a game without one is saved without one
+    TestCase::read("ext_24_played_at").run_and_compare_game_state();
+    // check that the new game is stamped with a time in the same format
+    let state: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("tests/cases/ext_24_played_at.run.json").unwrap(),
+    )
+    .unwrap();
+    let played_at = state["games"][2]["played_at"].as_str().unwrap_or("");
+    assert!(
+        played_at.len() == "2022-07-01T08:00:00.000Z".len() && played_at.ends_with('Z'),
+        "case ext_24_played_at incorrect: new game stamped with '{}'",
+        played_at
+    );
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_17_no_repeated_answer() {
+    // with only two final words, the answer of the second game is the other word
+    TestCase::read("ext_25_two_words").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_18_colorblind() {
+    // the colorblind mode only changes the colors, not the status letters
+    let case = TestCase::read("ext_26_colorblind");
+    case.run_and_compare_result();
+
+    // check the colors in TTY mode: blue for G, orange for Y and dark gray for R
+    #[cfg(target_os = "linux")]
+    if let Some(output) = case.run_in_tty() {
+        for colored in [
+            "\x1b[1;38;2;0;114;178mS\x1b[0m",
+            "\x1b[1;38;2;230;159;0mL\x1b[0m",
+            "\x1b[1;38;2;96;96;96mA\x1b[0m",
+        ] {
+            assert!(
+                output.contains(colored),
+                "case ext_26_colorblind incorrect: {:?} not in TTY output",
+                colored
+            );
+        }
+    }
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_19_solve() {
+    // the solver prints its guesses and the outcome without reading any input
+    TestCase::read("ext_27_solve").run_and_compare_result();
+    // the solver fails when the answer is not found within max attempts
+    TestCase::read("ext_28_solve_failed").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_20_solve_benchmark() {
+    // the solver is run against every final word
+    TestCase::read("ext_29_solve_benchmark").run_and_compare_result();
+    // some words need more guesses than max attempts
+    TestCase::read("ext_30_solve_benchmark_failed").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_21_adversarial() {
+    // the answer keeps the most candidates left, so every guess but the last one is wrong
+    TestCase::read("ext_31_adversarial").run_and_compare_result();
+    // the guess is only correct when it is the last candidate
+    TestCase::read("ext_32_adversarial_won").run_and_compare_result();
+    // the answer cannot be given in adversarial mode
+    TestCase::read("ext_33_adversarial_conflict").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_22_word_stats() {
+    // the games answered by the same word are counted together, won or lost
+    TestCase::read("ext_34_word_stats").run_and_compare_game_state();
+    // the answers with the lowest win rates are listed with `--stats --verbose`
+    TestCase::read("ext_35_hardest_answers").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_23_practice() {
+    // a practice game has unlimited attempts and is not recorded in the state file
+    let mut case = TestCase::read("ext_36_practice");
+    case.run_and_compare_game_state();
+
+    // check that the practice mode is indicated in TTY mode
+    #[cfg(target_os = "linux")]
+    if let Some(output) = case.run_in_tty() {
+        assert!(
+            output.contains("(Practice mode — not recorded)"),
+            "case ext_36_practice incorrect: practice mode not indicated"
+        );
+    }
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_24_letter_heatmap() {
+    // the letters of the answers and the guesses are counted by position
+    // check that repeated letters are counted in each of their positions
+    TestCase::read("ext_37_letter_heatmap").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(10000)]
+fn test_ext_25_server() {
+    use reqwest::blocking::Client;
+    use serde_json::{json, Value};
+
+    // the games are played over HTTP, the only final word is CRANE
+    let _server = TestCase::read("ext_38_server").run_server(12384);
+    let client = Client::new();
+    let url = |path: &str| format!("http://127.0.0.1:12384{}", path);
+
+    let response = client.post(url("/games")).send().unwrap();
+    assert_eq!(response.status(), 201);
+    let game: Value = response.json().unwrap();
+    assert_eq!(game["word_length"], 5);
+    let game_id = game["game_id"].as_str().unwrap().to_string();
+    let guess = |word: &str| {
+        client
+            .post(url(&format!("/games/{}/guess", game_id)))
+            .json(&json!({ "guess": word }))
+            .send()
+            .unwrap()
+    };
+
+    // a word not in the acceptable set is rejected and does not count as an attempt
+    assert_eq!(guess("ZZZZZ").status(), 400);
+    let response: Value = guess("slate").json().unwrap();
+    assert_eq!(response["status"], "RRGRG");
+    assert_eq!(response["state"], "continue");
+
+    // the answer is hidden until the game is over
+    let state: Value = client
+        .get(url(&format!("/games/{}", game_id)))
+        .send()
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(state["guesses"], json!(["SLATE"]));
+    assert_eq!(state["statuses"], json!(["RRGRG"]));
+    assert!(state.get("answer").is_none());
+
+    let response: Value = guess("CRANE").json().unwrap();
+    assert_eq!(response["status"], "GGGGG");
+    assert_eq!(response["state"], "won");
+    assert_eq!(response["answer"], "CRANE");
+    assert_eq!(guess("CRANE").status(), 409);
+
+    let unknown = client
+        .get(url("/games/00000000-0000-0000-0000-000000000000"))
+        .send()
+        .unwrap();
+    assert_eq!(unknown.status(), 404);
+
+    // the finished game is counted in the statistics of the session
+    let stats: Value = client.get(url("/stats")).send().unwrap().json().unwrap();
+    assert_eq!(stats["total_rounds"], 1);
+    assert_eq!(stats["success"], 1);
+    assert_eq!(stats["average_attempts"], 2.0);
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_26_day_and_seed_require_random() {
+    // `--day` and `--seed` only make sense with `--random`, even if the answer is given
+    TestCase::read("ext_39_word_with_day").run_and_expect_exit();
+    TestCase::read("ext_40_word_with_seed").run_and_expect_exit();
+    // the answer is read from the input otherwise
+    TestCase::read("ext_41_day_without_random").run_and_expect_exit();
+    TestCase::read("ext_42_seed_without_random").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_27_practice_replay() {
+    // playing once more in practice replays the same answer
+    TestCase::read("ext_43_practice_replay").run_and_compare_result();
+    // the seeded random sequence does not move on to the next day either
+    TestCase::read("ext_44_practice_random").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_28_empty_word_lists() {
+    // no game can be played with an empty word list
+    TestCase::read("ext_45_empty_final").run_and_expect_exit();
+    TestCase::read("ext_46_empty_acceptable").run_and_expect_exit();
+    // nor with a word which has characters other than the letters A to Z
+    TestCase::read("ext_92_non_letter_word").run_and_expect_exit();
+    // playing once more after the last final word of the random sequence is refused
+    TestCase::read("ext_47_day_out_of_range").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_29_reset_stats() {
+    // the games in the state file are cleared before playing
+    TestCase::read("ext_48_reset_stats").run_and_compare_game_state();
+    // practice is not recorded, so the state file is left as the empty statistics
+    TestCase::read("ext_49_reset_stats_practice").run_and_compare_game_state();
+    // there is nothing to reset without a state file
+    TestCase::read("ext_50_reset_stats_no_state").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(4000)]
+fn test_ext_30_reset_stats_confirm() {
+    // the reset has to be confirmed in TTY mode, unlike in non-TTY mode as in test_ext_29
+    // check that the history is kept when the player declines
+    #[cfg(target_os = "linux")]
+    for (name, total_rounds) in [
+        ("ext_51_reset_stats_declined", 2),
+        ("ext_52_reset_stats_confirmed", 1),
+    ] {
+        let case_dir = std::path::Path::new("tests").join("cases");
+        let run_state_file = case_dir.join(format!("{}.run.json", name));
+        std::fs::copy(
+            case_dir.join(format!("{}.before.json", name)),
+            &run_state_file,
+        )
+        .unwrap();
+        if let Some(output) = TestCase::read(name).run_in_tty() {
+            assert!(
+                output.contains("This will delete your game history. Are you sure? [Y/N]"),
+                "case {} incorrect: no confirmation prompt",
+                name
+            );
+            let state: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&run_state_file).unwrap()).unwrap();
+            assert_eq!(
+                state["total_rounds"], total_rounds,
+                "case {} incorrect: wrong number of games kept",
+                name
+            );
+        }
+    }
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_31_config_override() {
+    // the JSON file gives seed 7 and day 2, whose answer is CRANE
+    TestCase::read("ext_53_config_random").run_and_compare_result();
+    // the answer of day 1 is SLATE
+    TestCase::read("ext_54_config_no_day").run_and_compare_result();
+    // without a seed, the answer of day 2 is SLATE and the one of day 1 is CRANE
+    TestCase::read("ext_55_config_no_seed").run_and_compare_result();
+    TestCase::read("ext_56_config_no_seed_no_day").run_and_compare_result();
+    // the seed in the arguments replaces the one in the JSON file
+    TestCase::read("ext_60_config_seed").run_and_compare_result();
+    TestCase::read("ext_57_config_seed_conflict").run_and_expect_exit();
+    // the answer is read from the input instead of the JSON file
+    TestCase::read("ext_58_config_word").run_and_compare_result();
+    // null is the same as leaving the option out
+    TestCase::read("ext_59_config_null").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_32_import_nyt() {
+    // the finished NYT games are added to the state file in the order they were played
+    // check that the game in progress is left out
+    TestCase::read("ext_61_import_nyt").run_and_compare_game_state();
+    // a game won without guessing the solution is refused
+    TestCase::read("ext_62_import_nyt_invalid").run_and_expect_exit();
+    // there is no state file to merge the games into
+    TestCase::read("ext_63_import_nyt_no_state").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_33_settings_kept() {
+    // the settings saved by the GUI are written back with the new game
+    TestCase::read("ext_64_settings_kept").run_and_compare_game_state();
+    // resetting the statistics keeps the settings
+    TestCase::read("ext_65_settings_reset_stats").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(4000)]
+fn test_ext_34_verbose() {
+    // the candidates left and the best next guess are printed after each guess
+    TestCase::read("ext_66_verbose").run_and_compare_result();
+    // the answer is printed once the game is over, won or lost
+    TestCase::read("ext_67_verbose_lost").run_and_compare_result();
+}
+
+#[test]
+#[timeout(4000)]
+fn test_ext_35_resume() {
+    // the code printed with `--verbose` in test_ext_34 is the game after guessing SLATE against CRANE
+    TestCase::read("ext_68_resume").run_and_compare_result();
+    // the code is not valid base64
+    TestCase::read("ext_69_resume_malformed").run_and_expect_exit();
+    // the code gives one guess but has no bytes for it
+    TestCase::read("ext_70_resume_wrong_length").run_and_expect_exit();
+    // the indices are beyond the word lists
+    TestCase::read("ext_71_resume_unknown_answer").run_and_expect_exit();
+    TestCase::read("ext_74_resume_unknown_guess").run_and_expect_exit();
+    // the only guess of the code is the answer CRANE
+    TestCase::read("ext_72_resume_finished").run_and_expect_exit();
+    // the answer is given by the code
+    TestCase::read("ext_73_resume_word").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(4000)]
+fn test_ext_36_best_game() {
+    // the best game is only shown in TTY mode, the non-TTY output is unchanged
+    // CRANE and AUDIO are both won in 2 attempts, and the earlier CRANE is the best game
+    // the practice game is not recorded, so it does not count
+    let cases = [
+        ("ext_75_best_game", Some("CRANE\x1b[0m in \x1b[1m2")),
+        ("ext_76_best_game_word", Some("AUDIO\x1b[0m in \x1b[1m2")),
+        // SLATE has never been won
+        ("ext_77_best_game_word_lost", None),
+    ];
+    for (name, best_game) in cases {
+        let case = TestCase::read(name);
+        case.run_and_compare_result();
+
+        #[cfg(target_os = "linux")]
+        if let Some(output) = case.run_in_tty() {
+            match best_game {
+                Some(best_game) => assert!(
+                    output.contains(&format!("Best game: \x1b[1m{}\x1b[0m attempts", best_game)),
+                    "case {} incorrect: best game {:?} not in TTY output",
+                    name,
+                    best_game
+                ),
+                None => assert!(
+                    !output.contains("Best game"),
+                    "case {} incorrect: best game in TTY output",
+                    name
+                ),
+            }
+        }
+    }
+}
+
+#[test]
+#[timeout(4000)]
+fn test_ext_37_achievements() {
+    // each game earns the achievements whose conditions it meets, and only those
+    // an achievement earned before keeps its time
+    for name in [
+        "ext_78_achievement_first_win",
+        "ext_79_achievement_win_streak",
+        "ext_80_achievement_solve_in_1",
+        "ext_81_achievement_solve_in_2",
+        "ext_82_achievement_hundred_games",
+    ] {
+        TestCase::read(name).run_and_compare_game_state();
+    }
+
+    // the earned achievements are only listed in TTY mode
+    #[cfg(target_os = "linux")]
+    if let Some(output) = TestCase::read("ext_80_achievement_solve_in_1").run_in_tty() {
+        assert!(
+            output.contains("Achievements:") && output.contains("Hole in One"),
+            "case ext_80_achievement_solve_in_1 incorrect: achievements not in TTY output"
+        );
+    }
+
+    // the game of today ends a week of won games only if no game of the week was lost
+    let state_file = "tests/cases/ext_83_achievement_perfect_week.run.json";
+    for lost_day in [None, Some(3)] {
+        let games = (1..7)
+            .map(|days_ago| {
+                let played_at = chrono::Utc::now() - chrono::Duration::days(days_ago);
+                let guesses = match lost_day == Some(days_ago) {
+                    true => vec!["SLATE"; 6],
+                    false => vec!["CRANE"],
+                };
+                serde_json::json!({
+                    "answer": "CRANE",
+                    "guesses": guesses,
+                    "played_at": played_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+                })
+            })
+            .rev()
+            .collect::<Vec<_>>();
+        std::fs::write(
+            state_file,
+            serde_json::json!({ "total_rounds": 6, "games": games }).to_string(),
+        )
+        .unwrap();
+        TestCase::read("ext_83_achievement_perfect_week").run_and_compare_result();
+
+        let state: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(state_file).unwrap()).unwrap();
+        let earned = state["achievements"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|achievement| achievement["id"] == "perfect_week")
+            .unwrap()["earned_at"]
+            .is_string();
+        assert_eq!(
+            earned,
+            lost_day.is_none(),
+            "case ext_83_achievement_perfect_week incorrect: perfect week earned: {}",
+            earned
+        );
+    }
+}
+
+#[test]
+#[timeout(15000)]
+fn test_ext_38_guess_timeout() {
+    // the second guess comes after the time for it runs out, so it is taken as the third attempt
+    let pause = std::time::Duration::from_secs(3);
+    let case = TestCase::read("ext_84_timeout");
+    case.run_and_compare_result_with_pauses(pause);
+    // the game is lost when the time for the last attempt runs out
+    TestCase::read("ext_85_timeout_lost").run_and_compare_result_with_pauses(pause);
+    // the attempt given up still counts as one of the 2 attempts of the game
+    TestCase::read("ext_89_timeout_share").run_and_compare_result_with_pauses(pause);
+
+    // check that the seconds left are shown in TTY mode
+    #[cfg(target_os = "linux")]
+    if let Some(output) = case.run_in_tty() {
+        assert!(
+            output.contains("seconds left"),
+            "case ext_84_timeout incorrect: countdown not in TTY output"
+        );
+    }
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_39_total_rounds_from_games() {
+    // total_rounds in the state file is not trusted, it is counted again from the games
+    // whether it is out of date or missing, the saved state has 8 loaded games plus the new one
+    TestCase::read("ext_86_stale_total_rounds").run_and_compare_game_state();
+    TestCase::read("ext_87_missing_total_rounds").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_ext_40_timeout_play_again() {
+    // the answer of the next game is read from the same input as the guesses, which is read in the background
+    TestCase::read("ext_88_timeout_play_again").run_and_compare_result();
+}